@@ -0,0 +1,44 @@
+use d5::almanac::{parse_input, seed_ranges, Almanac, AlmanacSection};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const SAMPLES_PER_RANGE: i64 = 100_000;
+
+fn time_lookups(
+    almanac: &Almanac,
+    values: &[i64],
+    map: impl Fn(&AlmanacSection, i64) -> i64,
+) -> Duration {
+    let start = Instant::now();
+    for section in almanac.sections() {
+        for val in values {
+            black_box(map(section, black_box(*val)));
+        }
+    }
+    start.elapsed()
+}
+
+fn main() {
+    let (seeds, almanac) = parse_input(include_str!("../input.txt"));
+    let values: Vec<i64> = seed_ranges(&seeds)
+        .iter()
+        .flat_map(|r| {
+            let step = ((r.end - r.start) / SAMPLES_PER_RANGE).max(1);
+            r.clone().step_by(step as usize)
+        })
+        .collect();
+    let linear = time_lookups(&almanac, &values, |s, v| s.map_linear(v));
+    let indexed = time_lookups(&almanac, &values, |s, v| s.map(v));
+    let lookups = values.len() * almanac.sections().count();
+    println!("{} lookups per path", lookups);
+    println!(
+        "linear:  {:?} ({:.1} ns/lookup)",
+        linear,
+        linear.as_nanos() as f64 / lookups as f64
+    );
+    println!(
+        "indexed: {:?} ({:.1} ns/lookup)",
+        indexed,
+        indexed.as_nanos() as f64 / lookups as f64
+    );
+}
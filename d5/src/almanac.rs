@@ -0,0 +1,421 @@
+use crate::compiled::CompiledSection;
+use crate::graph::CategoryGraph;
+use crate::validate::{RangeIssue, ValidRange};
+use std::cmp::min;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+
+#[derive(Debug, Clone)]
+pub struct AlmanacRange {
+    pub(crate) from_start: i64,
+    pub(crate) to_start: i64,
+    pub(crate) length: i64,
+    pub(crate) line: usize,
+    validity: Result<ValidRange, RangeIssue>,
+}
+
+impl AlmanacRange {
+    /// A range read from line `line`, validated here once for every later
+    /// lookup.
+    pub fn new(from_start: i64, to_start: i64, length: i64, line: usize) -> Self {
+        Self {
+            from_start,
+            to_start,
+            length,
+            line,
+            validity: ValidRange::new(from_start, to_start, length),
+        }
+    }
+
+    pub fn validity(&self) -> Result<&ValidRange, &RangeIssue> {
+        self.validity.as_ref()
+    }
+
+    pub fn map(&self, val: i64) -> Option<i64> {
+        self.validity().ok()?.map(val)
+    }
+
+    /// The half-open interval of values this range maps, which is empty if
+    /// the range fails validation.
+    pub fn source(&self) -> Range<i64> {
+        match self.validity() {
+            Ok(valid) => valid.source().clone(),
+            Err(_) => self.from_start..self.from_start,
+        }
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Splits `range` against this range's source interval. Returns the part
+    /// that this range maps (already shifted to its destination), and the
+    /// parts on either side of it that it leaves alone.
+    pub fn map_range(&self, range: &Range<i64>) -> (Option<Range<i64>>, Vec<Range<i64>>) {
+        let Ok(valid) = self.validity() else {
+            return (None, vec![range.clone()]);
+        };
+        let source = valid.source();
+        let overlap = range.start.max(source.start)..range.end.min(source.end);
+        if overlap.is_empty() {
+            return (None, vec![range.clone()]);
+        }
+        let offset = valid.offset();
+        let mapped = (overlap.start + offset)..(overlap.end + offset);
+        let leftover = [range.start..overlap.start, overlap.end..range.end]
+            .into_iter()
+            .filter(|r| !r.is_empty())
+            .collect();
+        (Some(mapped), leftover)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AlmanacSection {
+    pub(crate) from: String,
+    pub(crate) to: String,
+    pub(crate) ranges: Vec<AlmanacRange>,
+    pub(crate) line: usize,
+    index: CompiledSection,
+}
+
+impl AlmanacSection {
+    pub fn new(from: &str, to: &str, ranges: Vec<AlmanacRange>, line: usize) -> Self {
+        let index = CompiledSection::from_ranges(from, to, &ranges);
+        Self {
+            from: from.to_string(),
+            to: to.to_string(),
+            ranges,
+            line,
+            index,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        format!("{}-to-{}", self.from, self.to)
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn ranges(&self) -> &[AlmanacRange] {
+        &self.ranges
+    }
+
+    pub fn index(&self) -> &CompiledSection {
+        &self.index
+    }
+
+    /// Looks `val` up by binary search over this section's sorted,
+    /// gap-filled breakpoint table.
+    pub fn map(&self, val: i64) -> i64 {
+        self.index.map(val)
+    }
+
+    /// The index of the first range that covers `val`, if any does.
+    pub fn find_range(&self, val: i64) -> Option<usize> {
+        self.ranges.iter().position(|r| r.map(val).is_some())
+    }
+
+    /// Looks `val` up by checking every range in turn.
+    pub fn map_linear(&self, val: i64) -> i64 {
+        let found = self.ranges.iter().find_map(|r| r.map(val));
+        match found {
+            Some(x) => x,
+            None => val,
+        }
+    }
+
+    /// Maps whole intervals at once, splitting them wherever they cross the
+    /// boundary of one of this section's ranges. Anything no range covers
+    /// passes through unchanged.
+    pub fn map_ranges(&self, ranges: &[Range<i64>]) -> Vec<Range<i64>> {
+        let mut unmapped: Vec<Range<i64>> = ranges.to_vec();
+        let mut mapped: Vec<Range<i64>> = Vec::new();
+        for almanac_range in &self.ranges {
+            let mut remaining = Vec::new();
+            for range in &unmapped {
+                let (hit, leftover) = almanac_range.map_range(range);
+                mapped.extend(hit);
+                remaining.extend(leftover);
+            }
+            unmapped = remaining;
+        }
+        mapped.extend(unmapped);
+        mapped
+    }
+}
+
+/// The sections of an almanac, along with the category graph they form and
+/// the seed-to-location route through it, both worked out once up front.
+#[derive(Debug)]
+pub struct Almanac {
+    maps: BTreeMap<String, BTreeMap<String, AlmanacSection>>,
+    pub(crate) graph: CategoryGraph,
+    seed_to_location: Option<Vec<String>>,
+}
+
+impl Almanac {
+    pub fn get_available_mappings(&self, from: &String) -> Vec<&String> {
+        match self.maps.get(from) {
+            Some(h) => h.keys().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn sections(&self) -> impl Iterator<Item = &AlmanacSection> {
+        self.maps.values().flat_map(|h| h.values())
+    }
+
+    pub fn get_section(&self, from: &String, to: &String) -> &AlmanacSection {
+        let section = self.maps.get(from).and_then(|h| h.get(to));
+        match section {
+            Some(sec) => sec,
+            None => panic!("Almanac is missing mapping {} -> {}", from, to),
+        }
+    }
+
+    /// Maps `val` through each section along `route`, a list of categories
+    /// such as the one returned by `Almanac::route`.
+    pub fn map_along(&self, route: &[String], val: i64) -> i64 {
+        route
+            .windows(2)
+            .fold(val, |acc, step| self.map(&step[0], &step[1], acc))
+    }
+
+    fn seed_to_location(&self) -> &[String] {
+        match &self.seed_to_location {
+            Some(route) => route,
+            None => panic!("Almanac has no route from seed to location"),
+        }
+    }
+
+    pub fn map(&self, from: &String, to: &String, val: i64) -> i64 {
+        self.get_section(from, to).map(val)
+    }
+
+    pub fn map_ranges(&self, from: &String, to: &String, ranges: &[Range<i64>]) -> Vec<Range<i64>> {
+        self.get_section(from, to).map_ranges(ranges)
+    }
+}
+
+pub fn parse_almanac_section(src: &str) -> AlmanacSection {
+    parse_almanac_section_at(src, 1)
+}
+
+/// Parses a section whose header sits on line `first_line` of the input, so
+/// that each range can remember where it came from.
+pub fn parse_almanac_section_at(src: &str, first_line: usize) -> AlmanacSection {
+    let mut by_line = src.split('\n');
+    let mut ranges: Vec<AlmanacRange> = Vec::new();
+    let from_to: Vec<&str> = by_line
+        .next()
+        .unwrap()
+        .split(' ')
+        .next()
+        .unwrap()
+        .split('-')
+        .collect();
+    for (idx, line) in by_line.enumerate() {
+        let entries: Vec<_> = line
+            .split(' ')
+            .filter_map(|x| x.parse::<i64>().ok())
+            .collect();
+        ranges.push(AlmanacRange::new(
+            entries[1],
+            entries[0],
+            entries[2],
+            first_line + idx + 1,
+        ))
+    }
+    AlmanacSection::new(from_to[0], from_to[2], ranges, first_line)
+}
+
+pub fn make_almanac(sections: Vec<AlmanacSection>) -> Almanac {
+    let mut maps: BTreeMap<String, BTreeMap<String, AlmanacSection>> = BTreeMap::new();
+    for section in sections {
+        let from = section.from.clone();
+        let to = section.to.clone();
+        let new_section = section.clone();
+        maps.entry(from).or_default().insert(to, new_section);
+    }
+    let graph = CategoryGraph::new(
+        maps.values()
+            .flat_map(|h| h.values())
+            .map(|section| (section.from.as_str(), section.to.as_str())),
+    );
+    let seed_to_location = graph.shortest_path("seed", "location");
+    Almanac {
+        maps,
+        graph,
+        seed_to_location,
+    }
+}
+
+pub fn parse_input(contents: &str) -> (Vec<i64>, Almanac) {
+    let mut sections: Vec<(&str, usize)> = Vec::new();
+    let mut line = 1;
+    for chunk in contents.split("\n\n") {
+        let leading = &chunk[..chunk.len() - chunk.trim_start().len()];
+        sections.push((chunk.trim(), line + leading.matches('\n').count()));
+        line += chunk.matches('\n').count() + 2;
+    }
+    let seeds: Vec<i64> = sections[0]
+        .0
+        .split(' ')
+        .filter_map(|x| x.parse::<i64>().ok())
+        .collect();
+    let sections: Vec<_> = sections[1..]
+        .iter()
+        .map(|(s, line)| parse_almanac_section_at(s, *line))
+        .collect();
+    (seeds, make_almanac(sections))
+}
+
+pub fn seed_ranges(seeds: &[i64]) -> Vec<Range<i64>> {
+    seeds
+        .chunks(2)
+        .filter_map(|window| match window {
+            [a, b] => Some(*a..(*a + *b)),
+            _ => None,
+        })
+        .collect()
+}
+
+pub fn lowest_location_for_seed(seed: i64, almanac: &Almanac) -> i64 {
+    almanac.map_along(almanac.seed_to_location(), seed)
+}
+
+pub fn lowest_location_for_seeds(seeds: &[i64], almanac: &Almanac) -> i64 {
+    let route = almanac.seed_to_location();
+    seeds.iter().fold(i64::MAX, |acc, next| {
+        min(acc, almanac.map_along(route, *next))
+    })
+}
+
+pub fn lowest_location_for_seed_ranges(seed_ranges: &[Range<i64>], almanac: &Almanac) -> i64 {
+    let mut ranges: Vec<Range<i64>> = seed_ranges.to_vec();
+    for step in almanac.seed_to_location().windows(2) {
+        ranges = almanac.map_ranges(&step[0], &step[1], &ranges);
+    }
+    ranges.iter().map(|r| r.start).min().unwrap_or(i64::MAX)
+}
+
+/// One category visited while tracing a seed, along with the index of the
+/// range that mapped the previous value into it (`None` if it passed
+/// through unchanged).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStep {
+    pub category: String,
+    pub value: i64,
+    pub matched: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeedTrace {
+    pub steps: Vec<TraceStep>,
+}
+
+impl SeedTrace {
+    pub fn location(&self) -> i64 {
+        self.steps.last().unwrap().value
+    }
+}
+
+impl fmt::Display for SeedTrace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (idx, step) in self.steps.iter().enumerate() {
+            if idx > 0 {
+                write!(f, " -> ")?;
+            }
+            write!(f, "{} {}", step.category, step.value)?;
+            match (idx, step.matched) {
+                (0, _) => {}
+                (_, Some(range)) => write!(f, " [range {}]", range)?,
+                (_, None) => write!(f, " [passed through]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Follows `seed` to its location the same way `lowest_location_for_seed`
+/// does, recording every value along the way.
+pub fn trace_seed(seed: i64, almanac: &Almanac) -> SeedTrace {
+    let route = almanac.seed_to_location();
+    let mut steps = vec![TraceStep {
+        category: route[0].clone(),
+        value: seed,
+        matched: None,
+    }];
+    for step in route.windows(2) {
+        let section = almanac.get_section(&step[0], &step[1]);
+        let value = steps.last().unwrap().value;
+        let matched = section.find_range(value);
+        let mapped = matched.and_then(|idx| section.ranges()[idx].map(value));
+        steps.push(TraceStep {
+            category: step[1].clone(),
+            value: mapped.unwrap_or(value),
+            matched,
+        });
+    }
+    SeedTrace { steps }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = include_str!("../input_test.txt");
+
+    #[test]
+    fn test_map_range_splits_at_boundaries() {
+        let range = AlmanacRange::new(10, 100, 5, 1);
+        let (mapped, leftover) = range.map_range(&(8..20));
+        assert_eq!(mapped, Some(100..105));
+        assert_eq!(leftover, vec![8..10, 15..20]);
+    }
+
+    #[test]
+    fn test_map_range_disjoint() {
+        let range = AlmanacRange::new(10, 100, 5, 1);
+        let (mapped, leftover) = range.map_range(&(15..20));
+        assert_eq!(mapped, None);
+        assert_eq!(leftover, vec![15..20]);
+    }
+
+    #[test]
+    fn test_seed_ranges_match_per_seed() {
+        let (seeds, almanac) = parse_input(TEST_INPUT);
+        let ranges = seed_ranges(&seeds);
+        let all_seeds: Vec<i64> = ranges.iter().cloned().flatten().collect();
+        let expected = lowest_location_for_seeds(&all_seeds, &almanac);
+        assert_eq!(lowest_location_for_seed_ranges(&ranges, &almanac), expected);
+        assert_eq!(expected, 46);
+    }
+
+    #[test]
+    fn test_trace_seed() {
+        let (_, almanac) = parse_input(TEST_INPUT);
+        let trace = trace_seed(79, &almanac);
+        assert_eq!(trace.location(), lowest_location_for_seed(79, &almanac));
+        assert_eq!(
+            trace.to_string(),
+            "seed 79 -> soil 81 [range 1] -> fertilizer 81 [passed through] -> \
+             water 81 [passed through] -> light 74 [range 1] -> temperature 78 [range 2] -> \
+             humidity 78 [passed through] -> location 82 [range 0]"
+        );
+    }
+
+    #[test]
+    fn test_trace_overlapping_ranges() {
+        let input = "seeds: 12\n\nseed-to-location map:\n0 10 10\n100 5 10";
+        let (_, almanac) = parse_input(input);
+        let trace = trace_seed(12, &almanac);
+        assert_eq!(trace.to_string(), "seed 12 -> location 2 [range 0]");
+        let section = almanac.get_section(&"seed".to_string(), &"location".to_string());
+        assert_eq!(section.ranges()[0].map(12), Some(trace.location()));
+    }
+}
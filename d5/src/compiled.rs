@@ -0,0 +1,292 @@
+use crate::almanac::{Almanac, AlmanacRange, AlmanacSection};
+use std::fmt;
+use std::ops::Range;
+
+/// One linear piece of a compiled mapping: every value in `start..end` is
+/// shifted by `offset`. The offset is wider than an `i64` because composing
+/// two large shifts can need one, even though every value it maps lands
+/// back inside `i64`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Piece {
+    pub start: i64,
+    pub end: i64,
+    pub offset: i128,
+}
+
+/// Shifts `val` by `offset`, clamping to the `i64` range.
+fn shift(val: i64, offset: i128) -> i64 {
+    (val as i128 + offset).clamp(i64::MIN as i128, i64::MAX as i128) as i64
+}
+
+impl Piece {
+    fn domain(&self) -> Range<i64> {
+        self.start..self.end
+    }
+
+    fn image(&self) -> Range<i64> {
+        shift(self.start, self.offset)..shift(self.end, self.offset)
+    }
+}
+
+/// A section flattened into sorted, non-overlapping pieces that cover every
+/// `i64`, so that unmapped values are explicit pieces with a zero offset.
+/// Unlike `AlmanacSection`, two of these can be composed into one.
+#[derive(Debug, Clone)]
+pub struct CompiledSection {
+    pub from: String,
+    pub to: String,
+    pieces: Vec<Piece>,
+}
+
+impl CompiledSection {
+    pub fn pieces(&self) -> &[Piece] {
+        &self.pieces
+    }
+
+    pub fn map(&self, val: i64) -> i64 {
+        let idx = self.pieces.partition_point(|p| p.end <= val);
+        shift(val, self.pieces.get(idx).map_or(0, |p| p.offset))
+    }
+
+    /// Builds the breakpoint table for a set of ranges. Where ranges overlap,
+    /// the one listed first wins, as it does for `AlmanacSection::map_linear`,
+    /// so each range only claims what earlier ones left uncovered. Ranges
+    /// that fail validation are left out, since they map nothing.
+    pub fn from_ranges(from: &str, to: &str, ranges: &[AlmanacRange]) -> Self {
+        let mut claimed: Vec<Piece> = Vec::new();
+        for range in ranges.iter().filter_map(|r| r.validity().ok()) {
+            let mut uncovered = vec![range.source().clone()];
+            for piece in &claimed {
+                uncovered = uncovered
+                    .into_iter()
+                    .flat_map(|r| {
+                        [
+                            r.start..r.end.min(piece.start),
+                            r.start.max(piece.end)..r.end,
+                        ]
+                    })
+                    .filter(|r| !r.is_empty())
+                    .collect();
+            }
+            claimed.extend(uncovered.into_iter().map(|r| Piece {
+                start: r.start,
+                end: r.end,
+                offset: range.offset() as i128,
+            }));
+        }
+        claimed.sort_by_key(|piece| piece.start);
+        let mut pieces = Vec::new();
+        let mut cursor = i64::MIN;
+        for piece in claimed {
+            if cursor < piece.start {
+                pieces.push(Piece {
+                    start: cursor,
+                    end: piece.start,
+                    offset: 0,
+                });
+            }
+            cursor = piece.end;
+            pieces.push(piece);
+        }
+        pieces.push(Piece {
+            start: cursor,
+            end: i64::MAX,
+            offset: 0,
+        });
+        let mut compiled = CompiledSection {
+            from: from.to_string(),
+            to: to.to_string(),
+            pieces,
+        };
+        compiled.merge_adjacent();
+        compiled
+    }
+
+    /// Joins neighbouring pieces that shift by the same offset.
+    pub fn merge_adjacent(&mut self) {
+        let mut merged: Vec<Piece> = Vec::with_capacity(self.pieces.len());
+        for piece in self.pieces.drain(..) {
+            match merged.last_mut() {
+                Some(last) if last.end == piece.start && last.offset == piece.offset => {
+                    last.end = piece.end;
+                }
+                _ => merged.push(piece),
+            }
+        }
+        self.pieces = merged;
+    }
+
+    /// Composes this mapping with `next`, yielding a single mapping from
+    /// `self.from` to `next.to`. Each piece of `self` is split wherever its
+    /// image crosses a breakpoint of `next`.
+    pub fn then(&self, next: &CompiledSection) -> CompiledSection {
+        assert!(
+            self.to == next.from,
+            "Cannot compose {} -> {} with {} -> {}",
+            self.from,
+            self.to,
+            next.from,
+            next.to
+        );
+        let mut pieces = Vec::new();
+        for piece in &self.pieces {
+            let image = piece.image();
+            let first = next.pieces.partition_point(|p| p.end <= image.start);
+            for next_piece in next.pieces[first..]
+                .iter()
+                .take_while(|p| p.start < image.end)
+            {
+                let start = piece.start.max(shift(next_piece.start, -piece.offset));
+                let end = piece.end.min(shift(next_piece.end, -piece.offset));
+                if start < end {
+                    pieces.push(Piece {
+                        start,
+                        end,
+                        offset: piece.offset + next_piece.offset,
+                    });
+                }
+            }
+        }
+        CompiledSection {
+            from: self.from.clone(),
+            to: next.to.clone(),
+            pieces,
+        }
+    }
+}
+
+impl From<&AlmanacSection> for CompiledSection {
+    fn from(section: &AlmanacSection) -> Self {
+        section.index().clone()
+    }
+}
+
+impl AlmanacSection {
+    /// An equivalent section with adjacent ranges that share an offset joined
+    /// into one, and ranges that map values onto themselves dropped.
+    pub fn merge_adjacent(&self) -> AlmanacSection {
+        let ranges = self
+            .index()
+            .pieces()
+            .iter()
+            .filter(|piece| piece.offset != 0)
+            .map(|piece| {
+                let line = self
+                    .ranges()
+                    .iter()
+                    .find(|r| r.source().contains(&piece.start))
+                    .map_or(self.line(), |r| r.line());
+                AlmanacRange::new(
+                    piece.start,
+                    shift(piece.start, piece.offset),
+                    piece.end - piece.start,
+                    line,
+                )
+            })
+            .collect();
+        AlmanacSection::new(&self.from, &self.to, ranges, self.line())
+    }
+}
+
+impl fmt::Display for CompiledSection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}-to-{}:", self.from, self.to)?;
+        for piece in &self.pieces {
+            let image = piece.image();
+            writeln!(
+                f,
+                "  [{}, {}) -> [{}, {}) ({:+})",
+                piece.domain().start,
+                piece.domain().end,
+                image.start,
+                image.end,
+                piece.offset
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Almanac {
+    /// Builds a single mapping for the whole chain of sections from `from`
+    /// to `to`.
+    pub fn compile(&self, from: &str, to: &str) -> CompiledSection {
+        let route = match self.route(from, to) {
+            Some(route) => route,
+            None => panic!("Almanac has no route from {} to {}", from, to),
+        };
+        let identity = CompiledSection {
+            from: from.to_string(),
+            to: from.to_string(),
+            pieces: vec![Piece {
+                start: i64::MIN,
+                end: i64::MAX,
+                offset: 0,
+            }],
+        };
+        route.windows(2).fold(identity, |so_far, step| {
+            let mut composed = so_far.then(self.get_section(&step[0], &step[1]).index());
+            composed.merge_adjacent();
+            composed
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::almanac::{lowest_location_for_seed, parse_input};
+
+    const TEST_INPUT: &str = include_str!("../input_test.txt");
+
+    #[test]
+    fn test_compiled_section_fills_gaps() {
+        let (_, almanac) = parse_input(TEST_INPUT);
+        let compiled = almanac.compile("seed", "soil");
+        let starts: Vec<_> = compiled.pieces().iter().map(|p| p.start).collect();
+        assert_eq!(starts, vec![i64::MIN, 50, 98, 100]);
+        assert_eq!(compiled.map(79), 81);
+        assert_eq!(compiled.map(99), 51);
+        assert_eq!(compiled.map(10), 10);
+    }
+
+    #[test]
+    fn test_compiled_matches_walk() {
+        let (seeds, almanac) = parse_input(TEST_INPUT);
+        let compiled = almanac.compile("seed", "location");
+        for seed in seeds {
+            assert_eq!(compiled.map(seed), lowest_location_for_seed(seed, &almanac));
+        }
+    }
+
+    #[test]
+    fn test_merge_adjacent() {
+        let input = "seeds: 1\n\nseed-to-location map:\n20 10 5\n25 15 5\n40 40 3\n0 50 2";
+        let (_, almanac) = parse_input(input);
+        let section = almanac.get_section(&"seed".to_string(), &"location".to_string());
+        let starts: Vec<_> = section.index().pieces().iter().map(|p| p.start).collect();
+        assert_eq!(starts, vec![i64::MIN, 10, 20, 50, 52]);
+        let merged = section.merge_adjacent();
+        assert_eq!(merged.ranges().len(), 2);
+        for val in 0..60 {
+            assert_eq!(merged.map_linear(val), section.map_linear(val));
+            assert_eq!(section.map(val), section.map_linear(val));
+        }
+    }
+
+    #[test]
+    fn test_overlapping_ranges_first_listed_wins() {
+        let input = "seeds: 12\n\nseed-to-soil map:\n0 10 10\n100 5 10\n50 0 30\n200 14 2";
+        let (_, almanac) = parse_input(input);
+        let section = almanac.get_section(&"seed".to_string(), &"soil".to_string());
+        assert_eq!(section.map_linear(12), 2);
+        for val in -5..40 {
+            assert_eq!(section.map(val), section.map_linear(val), "value {}", val);
+        }
+        let ranges = vec![-5..20, 20..40];
+        let mut by_interval: Vec<i64> = section.map_ranges(&ranges).into_iter().flatten().collect();
+        let mut by_value: Vec<i64> = (-5..40).map(|val| section.map(val)).collect();
+        by_interval.sort();
+        by_value.sort();
+        assert_eq!(by_interval, by_value);
+    }
+}
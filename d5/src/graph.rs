@@ -0,0 +1,219 @@
+use crate::almanac::Almanac;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// The categories of an almanac and which sections connect them. Neighbours
+/// are kept sorted so that every search visits them in the same order.
+#[derive(Debug, Clone, Default)]
+pub struct CategoryGraph {
+    edges: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl CategoryGraph {
+    pub fn new<'a>(edges: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut graph = Self::default();
+        for (from, to) in edges {
+            graph.edges.entry(to.to_string()).or_default();
+            graph
+                .edges
+                .entry(from.to_string())
+                .or_default()
+                .insert(to.to_string());
+        }
+        graph
+    }
+
+    pub fn categories(&self) -> impl Iterator<Item = &String> {
+        self.edges.keys()
+    }
+
+    pub fn neighbours(&self, from: &str) -> impl Iterator<Item = &String> {
+        self.edges.get(from).into_iter().flatten()
+    }
+
+    /// The route from `from` to `to` through the fewest sections, preferring
+    /// alphabetically earlier categories when several routes tie.
+    pub fn shortest_path(&self, from: &str, to: &str) -> Option<Vec<String>> {
+        let mut previous: BTreeMap<&str, &str> = BTreeMap::new();
+        let mut queue = VecDeque::from([from]);
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                let mut path = vec![to.to_string()];
+                let mut step = to;
+                while let Some(prev) = previous.get(step) {
+                    path.push(prev.to_string());
+                    step = prev;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for next in self.neighbours(current) {
+                if next != from && !previous.contains_key(next.as_str()) {
+                    previous.insert(next, current);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Every route from `from` to `to` that doesn't revisit a category.
+    pub fn all_paths(&self, from: &str, to: &str) -> Vec<Vec<String>> {
+        let mut paths = Vec::new();
+        let mut path = vec![from.to_string()];
+        self.extend_paths(to, &mut path, &mut paths);
+        paths
+    }
+
+    fn extend_paths(&self, to: &str, path: &mut Vec<String>, paths: &mut Vec<Vec<String>>) {
+        let current = path.last().unwrap().clone();
+        if current == to {
+            paths.push(path.clone());
+            return;
+        }
+        for next in self.neighbours(&current) {
+            if !path.contains(next) {
+                path.push(next.clone());
+                self.extend_paths(to, path, paths);
+                path.pop();
+            }
+        }
+    }
+
+    /// A cycle of categories, if there is one, listed from and back to the
+    /// same category.
+    pub fn find_cycle(&self) -> Option<Vec<String>> {
+        let mut finished: BTreeSet<&str> = BTreeSet::new();
+        for start in self.categories() {
+            let mut path: Vec<&str> = Vec::new();
+            if let Some(cycle) = self.find_cycle_from(start, &mut path, &mut finished) {
+                return Some(cycle);
+            }
+        }
+        None
+    }
+
+    fn find_cycle_from<'a>(
+        &'a self,
+        current: &'a str,
+        path: &mut Vec<&'a str>,
+        finished: &mut BTreeSet<&'a str>,
+    ) -> Option<Vec<String>> {
+        if let Some(idx) = path.iter().position(|c| *c == current) {
+            let mut cycle: Vec<String> = path[idx..].iter().map(|c| c.to_string()).collect();
+            cycle.push(current.to_string());
+            return Some(cycle);
+        }
+        if finished.contains(current) {
+            return None;
+        }
+        path.push(current);
+        for next in self.neighbours(current) {
+            if let Some(cycle) = self.find_cycle_from(next, path, finished) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        finished.insert(current);
+        None
+    }
+
+    /// Categories that can't be reached from `from` at all.
+    pub fn unreachable_from(&self, from: &str) -> Vec<String> {
+        let mut seen: BTreeSet<&str> = BTreeSet::from([from]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(current) = queue.pop_front() {
+            for next in self.neighbours(current) {
+                if seen.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        self.categories()
+            .filter(|c| !seen.contains(c.as_str()))
+            .cloned()
+            .collect()
+    }
+}
+
+impl Almanac {
+    /// The graph built when the almanac was made.
+    pub fn graph(&self) -> &CategoryGraph {
+        &self.graph
+    }
+
+    pub fn route(&self, from: &str, to: &str) -> Option<Vec<String>> {
+        self.graph.shortest_path(from, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::almanac::parse_input;
+
+    const TEST_INPUT: &str = include_str!("../input_test.txt");
+
+    fn branching_graph() -> CategoryGraph {
+        CategoryGraph::new([
+            ("seed", "soil"),
+            ("soil", "water"),
+            ("water", "location"),
+            ("seed", "light"),
+            ("light", "location"),
+            ("rock", "seed"),
+        ])
+    }
+
+    #[test]
+    fn test_route_through_test_almanac() {
+        let (_, almanac) = parse_input(TEST_INPUT);
+        let route = almanac.route("seed", "location").unwrap();
+        assert_eq!(route.len(), 8);
+        assert_eq!(route[1], "soil");
+        assert_eq!(almanac.graph().find_cycle(), None);
+        assert!(almanac.graph().unreachable_from("seed").is_empty());
+    }
+
+    #[test]
+    fn test_shortest_path_prefers_fewest_sections() {
+        let graph = branching_graph();
+        assert_eq!(
+            graph.shortest_path("seed", "location").unwrap(),
+            vec!["seed", "light", "location"]
+        );
+        assert_eq!(graph.shortest_path("location", "seed"), None);
+    }
+
+    #[test]
+    fn test_all_paths() {
+        let graph = branching_graph();
+        assert_eq!(
+            graph.all_paths("seed", "location"),
+            vec![
+                vec!["seed", "light", "location"],
+                vec!["seed", "soil", "water", "location"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unreachable_from() {
+        let graph = branching_graph();
+        assert_eq!(graph.unreachable_from("seed"), vec!["rock"]);
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let graph = CategoryGraph::new([
+            ("seed", "soil"),
+            ("soil", "water"),
+            ("water", "soil"),
+            ("water", "location"),
+        ]);
+        assert_eq!(graph.find_cycle().unwrap(), vec!["soil", "water", "soil"]);
+        assert_eq!(
+            graph.shortest_path("seed", "location").unwrap(),
+            vec!["seed", "soil", "water", "location"]
+        );
+    }
+}
@@ -0,0 +1,140 @@
+use crate::almanac::{Almanac, AlmanacRange, AlmanacSection};
+use std::ops::Range;
+
+impl AlmanacRange {
+    /// The value this range would map onto `val`, if `val` lies in its
+    /// destination interval.
+    pub fn inverse(&self, val: i64) -> Option<i64> {
+        let valid = self.validity().ok()?;
+        let preimage = val.checked_sub(valid.offset())?;
+        valid.map(preimage).filter(|mapped| *mapped == val)?;
+        Some(preimage)
+    }
+
+    fn boundaries(&self) -> Vec<i64> {
+        let source = self.source();
+        match source.is_empty() {
+            true => vec![],
+            false => vec![source.start, source.end],
+        }
+    }
+}
+
+impl AlmanacSection {
+    /// Every value that this section maps onto `val`. There can be several,
+    /// because `val` may be the image of a range and also pass through
+    /// unchanged when no range covers it.
+    pub fn inverse(&self, val: i64) -> Vec<i64> {
+        let mut preimages: Vec<i64> = self
+            .ranges
+            .iter()
+            .filter_map(|r| r.inverse(val))
+            .chain([val])
+            .filter(|preimage| self.map(*preimage) == val)
+            .collect();
+        preimages.sort();
+        preimages.dedup();
+        preimages
+    }
+}
+
+impl Almanac {
+    pub fn inverse(&self, from: &String, to: &String, val: i64) -> Vec<i64> {
+        self.get_section(from, to).inverse(val)
+    }
+
+    /// Every `from`-category value that maps onto the `to`-category `val`.
+    pub fn preimages(&self, from: &str, to: &str, val: i64) -> Vec<i64> {
+        let route = match self.route(from, to) {
+            Some(route) => route,
+            None => panic!("Almanac has no route from {} to {}", from, to),
+        };
+        self.preimages_along(&route, val)
+    }
+
+    fn preimages_along(&self, route: &[String], val: i64) -> Vec<i64> {
+        let mut values = vec![val];
+        for step in route.windows(2).rev() {
+            let (from, to) = (&step[0], &step[1]);
+            values = values
+                .iter()
+                .flat_map(|v| self.inverse(from, to, *v))
+                .collect();
+            values.sort();
+            values.dedup();
+        }
+        values
+    }
+}
+
+/// Finds the lowest location with a preimage inside one of `seed_ranges`.
+/// The minimum over a range of seeds can only occur at the start of that
+/// range or where some section's range boundary splits it, so only the
+/// locations those points map to need to be checked, in ascending order.
+pub fn lowest_location_by_inverse(seed_ranges: &[Range<i64>], almanac: &Almanac) -> Option<i64> {
+    let route = almanac.route("seed", "location")?;
+    let mut candidates: Vec<i64> = seed_ranges
+        .iter()
+        .map(|r| almanac.map_along(&route, r.start))
+        .collect();
+    for (idx, step) in route.windows(2).enumerate() {
+        let section = almanac.get_section(&step[0], &step[1]);
+        for boundary in section.ranges.iter().flat_map(|r| r.boundaries()) {
+            candidates.push(almanac.map_along(&route[idx..], boundary));
+        }
+    }
+    candidates.sort();
+    candidates.dedup();
+    candidates.into_iter().find(|location| {
+        almanac
+            .preimages_along(&route, *location)
+            .iter()
+            .any(|seed| seed_ranges.iter().any(|r| r.contains(seed)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::almanac::{
+        lowest_location_for_seed, lowest_location_for_seed_ranges, parse_input, seed_ranges,
+    };
+
+    const TEST_INPUT: &str = include_str!("../input_test.txt");
+
+    #[test]
+    fn test_section_inverse() {
+        let (_, almanac) = parse_input(TEST_INPUT);
+        let (seed, soil) = ("seed".to_string(), "soil".to_string());
+        assert_eq!(almanac.inverse(&seed, &soil, 81), vec![79]);
+        assert_eq!(almanac.inverse(&seed, &soil, 51), vec![99]);
+        assert_eq!(almanac.inverse(&seed, &soil, 14), vec![14]);
+    }
+
+    #[test]
+    fn test_preimages_map_back() {
+        let (seeds, almanac) = parse_input(TEST_INPUT);
+        for location in 0..110 {
+            for seed in almanac.preimages("seed", "location", location) {
+                assert_eq!(lowest_location_for_seed(seed, &almanac), location);
+            }
+        }
+        for seed in seeds {
+            let location = lowest_location_for_seed(seed, &almanac);
+            assert!(almanac
+                .preimages("seed", "location", location)
+                .contains(&seed));
+        }
+    }
+
+    #[test]
+    fn test_lowest_location_by_inverse() {
+        let (seeds, almanac) = parse_input(TEST_INPUT);
+        let ranges = seed_ranges(&seeds);
+        let expected = lowest_location_for_seed_ranges(&ranges, &almanac);
+        assert_eq!(
+            lowest_location_by_inverse(&ranges, &almanac),
+            Some(expected)
+        );
+    }
+}
@@ -0,0 +1,6 @@
+pub mod almanac;
+pub mod compiled;
+pub mod graph;
+pub mod inverse;
+pub mod serialize;
+pub mod validate;
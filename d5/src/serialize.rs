@@ -0,0 +1,215 @@
+use crate::almanac::{Almanac, AlmanacSection};
+use crate::graph::CategoryGraph;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+impl fmt::Display for AlmanacSection {
+    /// Writes the section back out in the `x-to-y map:` form it's parsed from.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} map:", self.name())?;
+        for range in self.ranges() {
+            write!(
+                f,
+                "\n{} {} {}",
+                range.to_start, range.from_start, range.length
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl CategoryGraph {
+    /// Categories ordered so that every section's source comes before its
+    /// destination. Categories caught up in a cycle go last, alphabetically.
+    pub fn topological_order(&self) -> Vec<String> {
+        let mut incoming: BTreeMap<&str, usize> =
+            self.categories().map(|c| (c.as_str(), 0)).collect();
+        for category in self.categories() {
+            for next in self.neighbours(category) {
+                *incoming.get_mut(next.as_str()).unwrap() += 1;
+            }
+        }
+        let mut ready: BTreeSet<&str> = incoming
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(c, _)| *c)
+            .collect();
+        let mut order: Vec<String> = Vec::new();
+        while let Some(current) = ready.pop_first() {
+            order.push(current.to_string());
+            for next in self.neighbours(current) {
+                let count = incoming.get_mut(next.as_str()).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    ready.insert(next);
+                }
+            }
+        }
+        for category in self.categories() {
+            if !order.contains(category) {
+                order.push(category.clone());
+            }
+        }
+        order
+    }
+}
+
+impl Almanac {
+    /// Sections in the order they'd appear in a hand-written almanac, seeds
+    /// first and locations last.
+    pub fn ordered_sections(&self) -> Vec<&AlmanacSection> {
+        let order = self.graph().topological_order();
+        let position = |category: &String| order.iter().position(|c| c == category);
+        let mut sections: Vec<_> = self.sections().collect();
+        sections.sort_by_key(|s| (position(&s.from), position(&s.to)));
+        sections
+    }
+
+    /// Every section laid out as an aligned table, one row per range.
+    pub fn to_table(&self) -> String {
+        let headers = ["source", "destination", "length", "offset"];
+        let mut out = String::new();
+        for section in self.ordered_sections() {
+            let rows: Vec<[String; 4]> = section
+                .ranges()
+                .iter()
+                .map(|r| match r.validity() {
+                    Ok(valid) => {
+                        let (source, destination) = (valid.source(), valid.destination());
+                        [
+                            format!("{}..{}", source.start, source.end),
+                            format!("{}..{}", destination.start, destination.end),
+                            r.length.to_string(),
+                            format!("{:+}", valid.offset()),
+                        ]
+                    }
+                    Err(_) => [
+                        format!("{}..", r.from_start),
+                        format!("{}..", r.to_start),
+                        r.length.to_string(),
+                        "invalid".to_string(),
+                    ],
+                })
+                .collect();
+            let widths: Vec<usize> = (0..headers.len())
+                .map(|col| {
+                    rows.iter()
+                        .map(|row| row[col].len())
+                        .chain([headers[col].len()])
+                        .max()
+                        .unwrap()
+                })
+                .collect();
+            out.push_str(&format!("{} (line {})\n", section.name(), section.line()));
+            let header: Vec<String> = headers
+                .iter()
+                .zip(&widths)
+                .map(|(h, w)| format!("{:>w$}", h, w = w))
+                .collect();
+            out.push_str(&format!("  {}\n", header.join(" | ")));
+            for row in rows {
+                let cells: Vec<String> = row
+                    .iter()
+                    .zip(&widths)
+                    .map(|(cell, w)| format!("{:>w$}", cell, w = w))
+                    .collect();
+                out.push_str(&format!("  {}\n", cells.join(" | ")));
+            }
+        }
+        out
+    }
+
+    /// The category graph as JSON: every category, and one edge per section
+    /// with the number of ranges it holds.
+    pub fn graph_to_json(&self) -> String {
+        let categories: Vec<String> = self
+            .graph()
+            .topological_order()
+            .iter()
+            .map(|c| json_string(c))
+            .collect();
+        let edges: Vec<String> = self
+            .ordered_sections()
+            .iter()
+            .map(|s| {
+                format!(
+                    "{{\"from\": {}, \"to\": {}, \"ranges\": {}}}",
+                    json_string(&s.from),
+                    json_string(&s.to),
+                    s.ranges().len()
+                )
+            })
+            .collect();
+        format!(
+            "{{\n  \"categories\": [{}],\n  \"edges\": [\n    {}\n  ]\n}}\n",
+            categories.join(", "),
+            edges.join(",\n    ")
+        )
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut out = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Writes `seeds` and `almanac` in the canonical input format, which
+/// `parse_input` reads back into the same almanac.
+pub fn write_almanac(seeds: &[i64], almanac: &Almanac) -> String {
+    let seeds: Vec<String> = seeds.iter().map(|s| s.to_string()).collect();
+    let mut out = format!("seeds: {}\n", seeds.join(" "));
+    for section in almanac.ordered_sections() {
+        out.push_str(&format!("\n{}\n", section));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::almanac::parse_input;
+
+    const TEST_INPUT: &str = include_str!("../input_test.txt");
+
+    #[test]
+    fn test_round_trip() {
+        let (seeds, almanac) = parse_input(TEST_INPUT);
+        let written = write_almanac(&seeds, &almanac);
+        assert_eq!(written.trim_end(), TEST_INPUT.trim_end());
+        let (reparsed_seeds, reparsed) = parse_input(&written);
+        assert_eq!(reparsed_seeds, seeds);
+        assert_eq!(write_almanac(&reparsed_seeds, &reparsed), written);
+    }
+
+    #[test]
+    fn test_graph_to_json() {
+        let (_, almanac) = parse_input("seeds: 1\n\nseed-to-soil map:\n1 2 3\n4 5 6");
+        assert_eq!(
+            almanac.graph_to_json(),
+            "{\n  \"categories\": [\"seed\", \"soil\"],\n  \"edges\": [\n    \
+             {\"from\": \"seed\", \"to\": \"soil\", \"ranges\": 2}\n  ]\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_to_table() {
+        let (_, almanac) = parse_input("seeds: 1\n\nseed-to-soil map:\n50 98 2");
+        assert_eq!(
+            almanac.to_table(),
+            concat!(
+                "seed-to-soil (line 3)\n",
+                "   source | destination | length | offset\n",
+                "  98..100 |      50..52 |      2 |    -48\n",
+            )
+        );
+    }
+}
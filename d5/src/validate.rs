@@ -0,0 +1,294 @@
+use crate::almanac::{Almanac, AlmanacSection};
+use std::fmt;
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeIssue {
+    /// The range has a length of zero, so it maps nothing.
+    ZeroLength,
+    /// The range has a negative length.
+    NegativeLength,
+    /// `from_start + length` or `to_start + length` doesn't fit in an `i64`.
+    Overflow,
+}
+
+/// An `AlmanacRange` known to be non-empty and to have both of its
+/// half-open intervals, and the offset between them, fit in an `i64`.
+/// Everything that maps values goes through one of these, so a range that
+/// fails validation maps nothing rather than overflowing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidRange {
+    source: Range<i64>,
+    offset: i64,
+}
+
+impl ValidRange {
+    pub fn source(&self) -> &Range<i64> {
+        &self.source
+    }
+
+    pub fn destination(&self) -> Range<i64> {
+        (self.source.start + self.offset)..(self.source.end + self.offset)
+    }
+
+    pub fn offset(&self) -> i64 {
+        self.offset
+    }
+
+    pub fn map(&self, val: i64) -> Option<i64> {
+        match self.source.contains(&val) {
+            true => Some(val + self.offset),
+            false => None,
+        }
+    }
+
+    pub fn overlaps(&self, other: &ValidRange) -> bool {
+        self.source.start < other.source.end && other.source.start < self.source.end
+    }
+}
+
+impl ValidRange {
+    /// Checks the numbers of an almanac line. `AlmanacRange::new` does this
+    /// once, so lookups never have to.
+    pub fn new(from_start: i64, to_start: i64, length: i64) -> Result<Self, RangeIssue> {
+        if length == 0 {
+            return Err(RangeIssue::ZeroLength);
+        }
+        if length < 0 {
+            return Err(RangeIssue::NegativeLength);
+        }
+        let source_end = from_start.checked_add(length);
+        let destination_end = to_start.checked_add(length);
+        let offset = to_start.checked_sub(from_start);
+        match (source_end, destination_end, offset) {
+            (Some(end), Some(_), Some(offset)) => Ok(Self {
+                source: from_start..end,
+                offset,
+            }),
+            _ => Err(RangeIssue::Overflow),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlmanacIssue {
+    Range {
+        section: String,
+        line: usize,
+        issue: RangeIssue,
+    },
+    Overlap {
+        section: String,
+        line: usize,
+        other_line: usize,
+    },
+}
+
+impl fmt::Display for AlmanacIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AlmanacIssue::Range {
+                section,
+                line,
+                issue: RangeIssue::ZeroLength,
+            } => write!(f, "line {}: {} has a zero-length range", line, section),
+            AlmanacIssue::Range {
+                section,
+                line,
+                issue: RangeIssue::NegativeLength,
+            } => write!(f, "line {}: {} has a negative-length range", line, section),
+            AlmanacIssue::Range {
+                section,
+                line,
+                issue: RangeIssue::Overflow,
+            } => write!(
+                f,
+                "line {}: {} has a range that overflows i64",
+                line, section
+            ),
+            AlmanacIssue::Overlap {
+                section,
+                line,
+                other_line,
+            } => write!(
+                f,
+                "line {}: {} has a range overlapping the one on line {}",
+                line, section, other_line
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    pub issues: Vec<AlmanacIssue>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+impl AlmanacSection {
+    pub fn validate(&self) -> Vec<AlmanacIssue> {
+        let mut issues = Vec::new();
+        let mut valid: Vec<(&ValidRange, usize)> = Vec::new();
+        for range in self.ranges() {
+            match range.validity() {
+                Ok(valid_range) => valid.push((valid_range, range.line())),
+                Err(issue) => issues.push(AlmanacIssue::Range {
+                    section: self.name(),
+                    line: range.line(),
+                    issue: issue.clone(),
+                }),
+            }
+        }
+        for (idx, (range, line)) in valid.iter().enumerate() {
+            for (other, other_line) in &valid[..idx] {
+                if range.overlaps(other) {
+                    issues.push(AlmanacIssue::Overlap {
+                        section: self.name(),
+                        line: *line,
+                        other_line: *other_line,
+                    });
+                }
+            }
+        }
+        issues.sort_by_key(|issue| match issue {
+            AlmanacIssue::Range { line, .. } | AlmanacIssue::Overlap { line, .. } => *line,
+        });
+        issues
+    }
+}
+
+impl Almanac {
+    pub fn validate(&self) -> ValidationReport {
+        let mut sections: Vec<_> = self.sections().collect();
+        sections.sort_by_key(|section| section.line());
+        ValidationReport {
+            issues: sections.iter().flat_map(|s| s.validate()).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::almanac::{lowest_location_for_seed, parse_input};
+
+    const TEST_INPUT: &str = include_str!("../input_test.txt");
+
+    #[test]
+    fn test_test_input_is_valid() {
+        let (_, almanac) = parse_input(TEST_INPUT);
+        assert!(almanac.validate().is_valid());
+    }
+
+    #[test]
+    fn test_range_is_half_open() {
+        let (_, almanac) = parse_input(TEST_INPUT);
+        let section = almanac.get_section(&"seed".to_string(), &"soil".to_string());
+        let range = section.ranges()[0].validity().unwrap();
+        assert_eq!(range.map(99), Some(51));
+        assert_eq!(range.map(100), None);
+        assert_eq!(section.map(100), 100);
+    }
+
+    #[test]
+    fn test_validate_reports_issues_by_line() {
+        let input = "seeds: 1 2\n\nseed-to-soil map:\n50 98 2\n10 99 5\n3 4 0\n\n\
+                     soil-to-location map:\n9223372036854775800 0 10";
+        let (_, almanac) = parse_input(input);
+        let report = almanac.validate();
+        assert_eq!(
+            report.issues,
+            vec![
+                AlmanacIssue::Overlap {
+                    section: "seed-to-soil".to_string(),
+                    line: 5,
+                    other_line: 4,
+                },
+                AlmanacIssue::Range {
+                    section: "seed-to-soil".to_string(),
+                    line: 6,
+                    issue: RangeIssue::ZeroLength,
+                },
+                AlmanacIssue::Range {
+                    section: "soil-to-location".to_string(),
+                    line: 9,
+                    issue: RangeIssue::Overflow,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_negative_length_is_its_own_issue() {
+        let (_, almanac) = parse_input("seeds: 1\n\nseed-to-soil map:\n50 98 -2");
+        let section = almanac.get_section(&"seed".to_string(), &"soil".to_string());
+        let issues = section.validate();
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(
+            issues[0],
+            AlmanacIssue::Range {
+                issue: RangeIssue::NegativeLength,
+                ..
+            }
+        ));
+        assert_eq!(section.map(97), 97);
+        assert!(section.ranges()[0].source().is_empty());
+    }
+
+    #[test]
+    fn test_overflowing_ranges_map_nothing() {
+        // The offset, the source end and the destination end each overflow.
+        let input = "seeds: 1\n\nseed-to-soil map:\n\
+                     9223372036854775807 -10 5\n\
+                     0 9223372036854775800 10\n\
+                     9223372036854775800 0 10";
+        let (_, almanac) = parse_input(input);
+        let section = almanac.get_section(&"seed".to_string(), &"soil".to_string());
+        let issues = section.validate();
+        assert_eq!(issues.len(), 3);
+        assert!(issues.iter().all(|issue| matches!(
+            issue,
+            AlmanacIssue::Range {
+                issue: RangeIssue::Overflow,
+                ..
+            }
+        )));
+        for val in [-8, 3, 9223372036854775801, i64::MAX] {
+            assert_eq!(section.map(val), val);
+            assert_eq!(section.map_linear(val), val);
+            assert_eq!(section.find_range(val), None);
+            assert_eq!(section.inverse(val), vec![val]);
+        }
+        let ranges = vec![-10..10, 20..30];
+        assert_eq!(section.map_ranges(&ranges), ranges);
+        assert!(section.ranges().iter().all(|r| r.source().is_empty()));
+        assert_eq!(almanac.compile("seed", "soil").pieces().len(), 1);
+        assert!(almanac.to_table().contains("invalid"));
+    }
+
+    #[test]
+    fn test_composed_offset_wider_than_i64() {
+        let input = "seeds: 1\n\nseed-to-soil map:\n0 -9000000000000000000 10\n\n\
+                     soil-to-location map:\n9000000000000000000 0 10";
+        let (_, almanac) = parse_input(input);
+        assert!(almanac.validate().is_valid());
+        let compiled = almanac.compile("seed", "location");
+        let seed = -9000000000000000000 + 3;
+        assert_eq!(compiled.map(seed), 9000000000000000003);
+        assert_eq!(compiled.map(seed), lowest_location_for_seed(seed, &almanac));
+    }
+}
@@ -0,0 +1,179 @@
+use crate::boat::BoatModel;
+use crate::race::{Race, RaceError, RaceInt};
+use std::fmt::{self, Display};
+use std::ops::RangeInclusive;
+
+/// Everything worth knowing about a race under a given boat model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RaceAnalysis<N = i64> {
+    /// The hold times that travel furthest. For the puzzle's linear boat
+    /// that's `T / 2`, and `T / 2 + 1` as well when `T` is odd.
+    pub optimal_hold_times: RangeInclusive<N>,
+    pub max_distance: N,
+    /// How far the best run beats the record by. Negative when the record
+    /// can't be beaten at all.
+    pub margin: N,
+    pub winning_hold_times: Option<RangeInclusive<N>>,
+    /// How much the record would have to rise before no hold time wins.
+    pub record_rise_to_lose: N,
+}
+
+impl<N: RaceInt> Race<N> {
+    pub fn analysis(&self, model: &dyn BoatModel<N>) -> Result<RaceAnalysis<N>, RaceError> {
+        let zero = N::zero();
+        let time = &self.allotted_time;
+        if *time < zero {
+            return Err(RaceError::InvalidNumber(time.to_string()));
+        }
+        let peak = model.peak_hold_time(time)?;
+        let max_distance = model.distance(time, &peak)?;
+        let margin = max_distance
+            .checked_sub(&self.record_distance)
+            .ok_or(RaceError::Overflow)?;
+        // The models are unimodal, so the hold times that reach the peak
+        // distance are exactly those that beat a record one short of it.
+        let just_short = Race {
+            allotted_time: time.clone(),
+            record_distance: max_distance
+                .checked_sub(&N::one())
+                .ok_or(RaceError::Overflow)?,
+        };
+        let optimal_hold_times = model
+            .winning_hold_times(&just_short)?
+            .unwrap_or(peak.clone()..=peak);
+        Ok(RaceAnalysis {
+            optimal_hold_times,
+            max_distance,
+            margin: margin.clone(),
+            winning_hold_times: model.winning_hold_times(self)?,
+            record_rise_to_lose: margin.max(zero),
+        })
+    }
+}
+
+impl<N: RaceInt> RaceAnalysis<N> {
+    pub fn ways_to_win(&self) -> N {
+        match &self.winning_hold_times {
+            Some(winners) => winners.end().clone() - winners.start().clone() + N::one(),
+            None => N::zero(),
+        }
+    }
+}
+
+impl<N: RaceInt> Display for RaceAnalysis<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "  optimal hold times:   [{}, {}]",
+            self.optimal_hold_times.start(),
+            self.optimal_hold_times.end()
+        )?;
+        writeln!(f, "  max distance:         {}", self.max_distance)?;
+        writeln!(f, "  margin over record:   {}", self.margin)?;
+        match &self.winning_hold_times {
+            Some(winners) => writeln!(
+                f,
+                "  winning interval:     [{}, {}] ({} ways)",
+                winners.start(),
+                winners.end(),
+                self.ways_to_win()
+            )?,
+            None => writeln!(f, "  winning interval:     none")?,
+        }
+        writeln!(f, "  record rise to lose:  {}", self.record_rise_to_lose)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boat::{Drag, LinearCharge, SpeedCap};
+    use crate::race::ways_to_win_race;
+
+    #[test]
+    fn test_analysis_odd_time() {
+        let race = Race {
+            allotted_time: 7,
+            record_distance: 9,
+        };
+        let analysis = race.analysis(&LinearCharge).unwrap();
+        assert_eq!(analysis.optimal_hold_times, 3..=4);
+        assert_eq!(analysis.max_distance, 12);
+        assert_eq!(analysis.margin, 3);
+        assert_eq!(analysis.winning_hold_times, Some(2..=5));
+        assert_eq!(analysis.record_rise_to_lose, 3);
+        assert_eq!(analysis.ways_to_win(), ways_to_win_race(&race));
+    }
+
+    #[test]
+    fn test_analysis_even_time() {
+        let race: Race = Race {
+            allotted_time: 30,
+            record_distance: 200,
+        };
+        let analysis = race.analysis(&LinearCharge).unwrap();
+        assert_eq!(analysis.optimal_hold_times, 15..=15);
+        assert_eq!(analysis.max_distance, 225);
+        assert_eq!(analysis.winning_hold_times, Some(11..=19));
+    }
+
+    #[test]
+    fn test_analysis_unwinnable() {
+        let race: Race = Race {
+            allotted_time: 10,
+            record_distance: 25,
+        };
+        let analysis = race.analysis(&LinearCharge).unwrap();
+        assert_eq!(analysis.margin, 0);
+        assert_eq!(analysis.winning_hold_times, None);
+        assert_eq!(analysis.record_rise_to_lose, 0);
+        let harder = Race {
+            record_distance: 30,
+            ..race
+        };
+        assert_eq!(harder.analysis(&LinearCharge).unwrap().margin, -5);
+    }
+
+    #[test]
+    fn test_raising_record_by_rise_removes_winners() {
+        for allotted_time in 0..30 {
+            for record_distance in -3..(allotted_time * allotted_time / 4 + 3) {
+                let race = Race {
+                    allotted_time,
+                    record_distance,
+                };
+                let analysis = race.analysis(&LinearCharge).unwrap();
+                let raised = Race {
+                    allotted_time,
+                    record_distance: record_distance + analysis.record_rise_to_lose,
+                };
+                assert_eq!(ways_to_win_race(&raised), 0);
+                if analysis.record_rise_to_lose > 0 {
+                    let almost = Race {
+                        allotted_time,
+                        record_distance: raised.record_distance - 1,
+                    };
+                    assert!(ways_to_win_race(&almost) > 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_analysis_uses_the_model() {
+        let race: Race = Race {
+            allotted_time: 7,
+            record_distance: 5,
+        };
+        let capped = race.analysis(&SpeedCap::new(2).unwrap()).unwrap();
+        assert_eq!(capped.optimal_hold_times, 2..=2);
+        assert_eq!(capped.max_distance, 10);
+        assert_eq!(capped.margin, 5);
+        assert_eq!(capped.winning_hold_times, Some(1..=4));
+        let stalled = race.analysis(&Drag::new(100).unwrap()).unwrap();
+        assert_eq!(stalled.optimal_hold_times, 0..=7);
+        assert_eq!(stalled.max_distance, 0);
+        assert_eq!(stalled.winning_hold_times, None);
+        assert_eq!(stalled.record_rise_to_lose, 0);
+    }
+}
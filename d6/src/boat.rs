@@ -0,0 +1,450 @@
+use crate::race::{checked_winning_hold_times, Race, RaceError, RaceInt};
+use std::ops::RangeInclusive;
+
+fn checked<N>(value: Option<N>) -> Result<N, RaceError> {
+    value.ok_or(RaceError::Overflow)
+}
+
+/// How far a boat travels given how long its button was held, measured in
+/// `N`. Every model is unimodal in the hold time: distance never falls
+/// before `peak_hold_time` and never rises after it, so the winning hold
+/// times always form a single interval around the peak. Anything that
+/// doesn't fit in `N` is reported as `RaceError::Overflow`.
+pub trait BoatModel<N: RaceInt = i64> {
+    fn distance(&self, allotted_time: &N, hold_time: &N) -> Result<N, RaceError>;
+
+    /// A hold time at which `distance` is greatest.
+    fn peak_hold_time(&self, allotted_time: &N) -> Result<N, RaceError>;
+
+    /// Holding for less than nothing or for longer than the race never wins.
+    fn can_win(&self, race: &Race<N>, hold_time: &N) -> Result<bool, RaceError> {
+        if *hold_time < N::zero() || *hold_time > race.allotted_time {
+            return Ok(false);
+        }
+        Ok(self.distance(&race.allotted_time, hold_time)? > race.record_distance)
+    }
+
+    /// Models without a closed form find each end of the interval by binary
+    /// search outwards from the peak.
+    fn winning_hold_times(&self, race: &Race<N>) -> Result<Option<RangeInclusive<N>>, RaceError> {
+        let (zero, one) = (N::zero(), N::one());
+        let two = one.clone() + one.clone();
+        if race.allotted_time < zero {
+            return Ok(None);
+        }
+        let peak = self.peak_hold_time(&race.allotted_time)?;
+        if !self.can_win(race, &peak)? {
+            return Ok(None);
+        }
+        let (mut lo, mut hi) = (zero, peak.clone());
+        while lo < hi {
+            let mid = lo.clone() + (hi.clone() - lo.clone()) / two.clone();
+            match self.can_win(race, &mid)? {
+                true => hi = mid,
+                false => lo = mid + one.clone(),
+            }
+        }
+        let first = lo;
+        let (mut lo, mut hi) = (peak, race.allotted_time.clone());
+        while lo < hi {
+            let mid = lo.clone() + (hi.clone() - lo.clone() + one.clone()) / two.clone();
+            match self.can_win(race, &mid)? {
+                true => lo = mid,
+                false => hi = mid - one.clone(),
+            }
+        }
+        Ok(Some(first..=lo))
+    }
+
+    fn ways_to_win(&self, race: &Race<N>) -> Result<N, RaceError> {
+        match self.winning_hold_times(race)? {
+            Some(winners) => {
+                let (lo, hi) = winners.into_inner();
+                checked((hi - lo).checked_add(&N::one()))
+            }
+            None => Ok(N::zero()),
+        }
+    }
+}
+
+/// The puzzle's boat: each unit of hold time adds one unit of speed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinearCharge;
+
+impl<N: RaceInt> BoatModel<N> for LinearCharge {
+    fn distance(&self, allotted_time: &N, hold_time: &N) -> Result<N, RaceError> {
+        checked(checked(allotted_time.checked_sub(hold_time))?.checked_mul(hold_time))
+    }
+
+    fn peak_hold_time(&self, allotted_time: &N) -> Result<N, RaceError> {
+        Ok(allotted_time.clone() / N::from(2))
+    }
+
+    fn winning_hold_times(&self, race: &Race<N>) -> Result<Option<RangeInclusive<N>>, RaceError> {
+        checked_winning_hold_times(race)
+    }
+}
+
+/// Speed grows with the square of the hold time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuadraticAcceleration;
+
+impl<N: RaceInt> BoatModel<N> for QuadraticAcceleration {
+    fn distance(&self, allotted_time: &N, hold_time: &N) -> Result<N, RaceError> {
+        let travel_time = checked(allotted_time.checked_sub(hold_time))?;
+        checked(checked(hold_time.checked_mul(hold_time))?.checked_mul(&travel_time))
+    }
+
+    /// The curve peaks at `2T / 3`, so the best whole hold time is one side
+    /// of it or the other.
+    fn peak_hold_time(&self, allotted_time: &N) -> Result<N, RaceError> {
+        let estimate = checked(allotted_time.checked_mul(&N::from(2)))? / N::from(3);
+        let next = (estimate.clone() + N::one()).min(allotted_time.clone());
+        match self.distance(allotted_time, &next)? >= self.distance(allotted_time, &estimate)? {
+            true => Ok(next),
+            false => Ok(estimate),
+        }
+    }
+}
+
+/// Linear charge-up, but speed can't exceed `cap`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpeedCap {
+    cap: i64,
+}
+
+impl SpeedCap {
+    pub fn new(cap: i64) -> Result<Self, String> {
+        match cap >= 0 {
+            true => Ok(Self { cap }),
+            false => Err(format!("speed cap {} is negative", cap)),
+        }
+    }
+
+    pub fn cap(&self) -> i64 {
+        self.cap
+    }
+}
+
+impl<N: RaceInt> BoatModel<N> for SpeedCap {
+    fn distance(&self, allotted_time: &N, hold_time: &N) -> Result<N, RaceError> {
+        let speed = hold_time.clone().min(N::from(self.cap));
+        checked(speed.checked_mul(&checked(allotted_time.checked_sub(hold_time))?))
+    }
+
+    fn peak_hold_time(&self, allotted_time: &N) -> Result<N, RaceError> {
+        Ok((allotted_time.clone() / N::from(2)).min(N::from(self.cap)))
+    }
+}
+
+/// Linear charge-up, with drag taking `percent` of the distance covered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Drag {
+    percent: i64,
+}
+
+impl Drag {
+    /// `percent` must be between 0 and 100, or the distance could go
+    /// negative and the model would no longer be unimodal.
+    pub fn new(percent: i64) -> Result<Self, String> {
+        match (0..=100).contains(&percent) {
+            true => Ok(Self { percent }),
+            false => Err(format!("drag {}% isn't between 0% and 100%", percent)),
+        }
+    }
+
+    pub fn percent(&self) -> i64 {
+        self.percent
+    }
+}
+
+impl<N: RaceInt> BoatModel<N> for Drag {
+    fn distance(&self, allotted_time: &N, hold_time: &N) -> Result<N, RaceError> {
+        let undragged = LinearCharge.distance(allotted_time, hold_time)?;
+        Ok(checked(undragged.checked_mul(&N::from(100 - self.percent)))? / N::from(100))
+    }
+
+    fn peak_hold_time(&self, allotted_time: &N) -> Result<N, RaceError> {
+        Ok(allotted_time.clone() / N::from(2))
+    }
+}
+
+/// Linear charge-up, but the boat spends `cost` of its travel time turning
+/// around before it moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Turnaround {
+    cost: i64,
+}
+
+impl Turnaround {
+    pub fn new(cost: i64) -> Result<Self, String> {
+        match cost >= 0 {
+            true => Ok(Self { cost }),
+            false => Err(format!("turnaround cost {} is negative", cost)),
+        }
+    }
+
+    pub fn cost(&self) -> i64 {
+        self.cost
+    }
+
+    fn time_after_turning<N: RaceInt>(&self, allotted_time: &N) -> Result<N, RaceError> {
+        checked(allotted_time.checked_sub(&N::from(self.cost)))
+    }
+}
+
+impl<N: RaceInt> BoatModel<N> for Turnaround {
+    fn distance(&self, allotted_time: &N, hold_time: &N) -> Result<N, RaceError> {
+        let moving_time = checked(
+            self.time_after_turning(allotted_time)?
+                .checked_sub(hold_time),
+        )?;
+        checked(hold_time.checked_mul(&moving_time.max(N::zero())))
+    }
+
+    fn peak_hold_time(&self, allotted_time: &N) -> Result<N, RaceError> {
+        Ok(self.time_after_turning(allotted_time)?.max(N::zero()) / N::from(2))
+    }
+
+    /// The same race as `LinearCharge` with `cost` less time, unless the
+    /// record is negative and even standing still wins.
+    fn winning_hold_times(&self, race: &Race<N>) -> Result<Option<RangeInclusive<N>>, RaceError> {
+        if race.allotted_time < N::zero() {
+            return Ok(None);
+        }
+        if race.record_distance < N::zero() {
+            return Ok(Some(N::zero()..=race.allotted_time.clone()));
+        }
+        checked_winning_hold_times(&Race {
+            allotted_time: self.time_after_turning(&race.allotted_time)?,
+            record_distance: race.record_distance.clone(),
+        })
+    }
+}
+
+/// A model read from a spec, which can run races at any width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Boat {
+    Linear(LinearCharge),
+    Quadratic(QuadraticAcceleration),
+    SpeedCap(SpeedCap),
+    Drag(Drag),
+    Turnaround(Turnaround),
+}
+
+impl Default for Boat {
+    fn default() -> Self {
+        Boat::Linear(LinearCharge)
+    }
+}
+
+impl Boat {
+    pub fn model<N: RaceInt>(&self) -> &dyn BoatModel<N> {
+        match self {
+            Boat::Linear(model) => model,
+            Boat::Quadratic(model) => model,
+            Boat::SpeedCap(model) => model,
+            Boat::Drag(model) => model,
+            Boat::Turnaround(model) => model,
+        }
+    }
+}
+
+/// Reads a model from a spec such as `linear`, `quadratic`, `speed-cap 40`,
+/// `drag 10` or `turnaround 3`.
+pub fn parse_boat_model(spec: &str) -> Result<Boat, String> {
+    let words: Vec<_> = spec.split_ascii_whitespace().collect();
+    let param = |name: &str| match words.get(1).map(|w| w.parse::<i64>()) {
+        Some(Ok(value)) if value >= 0 => Ok(value),
+        _ => Err(format!("{} needs a non-negative number", name)),
+    };
+    match words.first().copied() {
+        Some("linear") => Ok(Boat::Linear(LinearCharge)),
+        Some("quadratic") => Ok(Boat::Quadratic(QuadraticAcceleration)),
+        Some("speed-cap") => Ok(Boat::SpeedCap(SpeedCap::new(param("speed-cap")?)?)),
+        Some("drag") => Ok(Boat::Drag(Drag::new(param("drag")?.min(100))?)),
+        Some("turnaround") => Ok(Boat::Turnaround(Turnaround::new(param("turnaround")?)?)),
+        _ => Err(format!("Unknown boat model: {}", spec)),
+    }
+}
+
+/// Multiplies together each race's ways to win, widening the races to `N`
+/// first so that the whole product is computed at that width.
+pub fn product_of_ways_to_win<N: RaceInt>(
+    model: &dyn BoatModel<N>,
+    races: &[Race],
+) -> Result<N, RaceError> {
+    races.iter().try_fold(N::one(), |product, race| {
+        checked(product.checked_mul(&model.ways_to_win(&race.widen())?))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sheet::{parse_kerned_race, parse_race_sheet};
+    use num_bigint::BigInt;
+
+    fn brute_force(model: &dyn BoatModel, race: &Race) -> i64 {
+        (0..=race.allotted_time)
+            .filter(|h| model.can_win(race, h).unwrap())
+            .count() as i64
+    }
+
+    fn check_against_brute_force(model: &dyn BoatModel) {
+        for allotted_time in 0..40 {
+            let most = (0..=allotted_time)
+                .map(|h| model.distance(&allotted_time, &h).unwrap())
+                .max()
+                .unwrap();
+            for record_distance in -3..(most + 3) {
+                let race = Race {
+                    allotted_time,
+                    record_distance,
+                };
+                assert_eq!(
+                    model.ways_to_win(&race),
+                    Ok(brute_force(model, &race)),
+                    "{:?}",
+                    race
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_linear_charge() {
+        check_against_brute_force(&LinearCharge);
+    }
+
+    #[test]
+    fn test_quadratic_acceleration() {
+        check_against_brute_force(&QuadraticAcceleration);
+    }
+
+    #[test]
+    fn test_speed_cap() {
+        for cap in [0, 1, 5, 30] {
+            check_against_brute_force(&SpeedCap::new(cap).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_drag() {
+        for percent in [0, 10, 50, 100] {
+            check_against_brute_force(&Drag::new(percent).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_turnaround() {
+        for cost in [0, 1, 7, 50] {
+            check_against_brute_force(&Turnaround::new(cost).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_constructors_reject_bad_parameters() {
+        assert!(SpeedCap::new(-1).is_err());
+        assert!(Drag::new(-1).is_err());
+        assert!(Drag::new(150).is_err());
+        assert_eq!(Drag::new(100).unwrap().percent(), 100);
+        assert!(Turnaround::new(-3).is_err());
+        let boat = parse_boat_model("drag 150").unwrap();
+        assert_eq!(boat, Boat::Drag(Drag::new(100).unwrap()));
+    }
+
+    #[test]
+    fn test_hold_outside_race_loses() {
+        let race = Race {
+            allotted_time: 7,
+            record_distance: -5,
+        };
+        for boat in [
+            "linear",
+            "quadratic",
+            "speed-cap 2",
+            "drag 10",
+            "turnaround 1",
+        ] {
+            let model = parse_boat_model(boat).unwrap();
+            assert_eq!(
+                model.model::<i64>().can_win(&race, &8),
+                Ok(false),
+                "{}",
+                boat
+            );
+            assert_eq!(
+                model.model::<i64>().can_win(&race, &-1),
+                Ok(false),
+                "{}",
+                boat
+            );
+            assert_eq!(
+                model.model::<i64>().can_win(&race, &7),
+                Ok(true),
+                "{}",
+                boat
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_boat_model() {
+        let boat = parse_boat_model("speed-cap 3").unwrap();
+        assert_eq!(boat, Boat::SpeedCap(SpeedCap::new(3).unwrap()));
+        assert_eq!(boat.model::<i64>().distance(&10, &5), Ok(15));
+        assert!(parse_boat_model("speed-cap").is_err());
+        assert!(parse_boat_model("rowing").is_err());
+    }
+
+    #[test]
+    fn test_overflow_is_reported_and_wider_types_agree() {
+        let race = Race {
+            allotted_time: 8_000_000_000_i64,
+            record_distance: 1_000_000_000,
+        };
+        let wide = race.widen::<i128>();
+        for spec in [
+            "linear",
+            "quadratic",
+            "speed-cap 5000000000",
+            "drag 10",
+            "turnaround 3",
+        ] {
+            let boat = parse_boat_model(spec).unwrap();
+            assert_eq!(
+                boat.model().ways_to_win(&race),
+                Err(RaceError::Overflow),
+                "{}",
+                spec
+            );
+            let ways = boat.model().ways_to_win(&wide).unwrap();
+            let big = boat.model().ways_to_win(&race.widen::<BigInt>()).unwrap();
+            assert_eq!(BigInt::from(ways), big, "{}", spec);
+            assert!(ways > 0, "{}", spec);
+        }
+    }
+
+    #[test]
+    fn test_oversized_sheet_with_boat_widens() {
+        let text = "Time: 4000000000 5\nDistance: 1000000000 3\n";
+        for spec in ["linear", "drag 10"] {
+            let sheet = parse_race_sheet(&format!("{}Boat: {}\n", text, spec)).unwrap();
+            let boat = sheet.boat.unwrap();
+            assert_eq!(
+                product_of_ways_to_win::<i64>(boat.model(), &sheet.races),
+                Err(RaceError::Overflow)
+            );
+            let product = product_of_ways_to_win::<i128>(boat.model(), &sheet.races).unwrap();
+            let big = product_of_ways_to_win::<BigInt>(boat.model(), &sheet.races).unwrap();
+            assert_eq!(BigInt::from(product), big);
+            let kerned = parse_kerned_race::<i128>(text).unwrap();
+            assert!(boat.model().ways_to_win(&kerned).unwrap() > 0);
+        }
+        let boat = parse_boat_model("linear").unwrap();
+        let product =
+            product_of_ways_to_win::<i128>(boat.model(), &parse_race_sheet(text).unwrap().races);
+        assert_eq!(product, Ok(4 * (4_000_000_000 - 1)));
+    }
+}
@@ -0,0 +1,253 @@
+use crate::race::{checked_ways_to_win_race, widening, Race, RaceInt};
+use crate::sheet::{parse_kerned_race, write_race_sheet};
+use num_bigint::BigInt;
+use std::error::Error;
+use std::ops::RangeInclusive;
+
+/// Every record that gives a race of `allotted_time` the wanted number of
+/// ways to win.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RaceDesign {
+    pub allotted_time: i64,
+    pub records: RangeInclusive<i64>,
+}
+
+/// The design for races of `allotted_time` with exactly `ways` winning hold
+/// times and a non-negative record. The winners of a race are
+/// `[lo, T - lo]`, so `T - 2 * lo + 1 = ways` fixes `lo` for each `T` of the
+/// right parity, and the record must lie between the distance at `lo - 1`
+/// (which has to lose) and the distance at `lo` (which has to win). `None`
+/// when there's no such design or its records don't fit in `i64`.
+///
+/// With `ways == 0` any record from the best possible distance upwards
+/// works; only the first `T + 1` of those are listed.
+fn design_for(ways: i64, allotted_time: i64) -> Option<RaceDesign> {
+    if ways == 0 {
+        let best = (allotted_time / 2).checked_mul(allotted_time - allotted_time / 2)?;
+        return Some(RaceDesign {
+            allotted_time,
+            records: best..=best.checked_add(allotted_time)?,
+        });
+    }
+    if ways < 0 || allotted_time <= ways || (allotted_time - ways) % 2 == 0 {
+        return None;
+    }
+    let lo = (allotted_time + 1 - ways) / 2;
+    let losing = (lo - 1).checked_mul(allotted_time - lo + 1)?;
+    let winning = lo.checked_mul(allotted_time - lo)?;
+    Some(RaceDesign {
+        allotted_time,
+        records: losing..=(winning - 1),
+    })
+}
+
+/// The first allotted time with a design for `ways`, and the step to the
+/// next one.
+fn design_times(ways: i64) -> (i64, i64) {
+    match ways {
+        0 => (0, 1),
+        _ => (ways + 1, 2),
+    }
+}
+
+/// Lists the race designs with exactly `ways` winning hold times and a
+/// non-negative record, shortest races first, until their records no longer
+/// fit in `i64`. See `design_for`.
+pub fn race_designs(ways: i64) -> Box<dyn Iterator<Item = RaceDesign>> {
+    if ways < 0 {
+        return Box::new(std::iter::empty());
+    }
+    let (first, step) = design_times(ways);
+    Box::new(
+        (first..)
+            .step_by(step as usize)
+            .map_while(move |allotted_time| design_for(ways, allotted_time)),
+    )
+}
+
+pub fn race_designs_within(ways: i64, time_budget: i64) -> impl Iterator<Item = RaceDesign> {
+    race_designs(ways).take_while(move |design| design.allotted_time <= time_budget)
+}
+
+/// A small, seedable generator so that sampled fixtures can be reproduced.
+#[derive(Debug, Clone)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// A value in `range`, which must not be empty.
+    pub fn in_range(&mut self, range: &RangeInclusive<i64>) -> i64 {
+        let span = (*range.end() as i128 - *range.start() as i128 + 1) as u128;
+        let offset = (self.next_u64() as u128 % span) as i128;
+        (*range.start() as i128 + offset) as i64
+    }
+}
+
+/// Picks a race at random among those with exactly `ways` ways to win and
+/// an allotted time of at most `time_budget`, each design being equally
+/// likely. Designs are picked by index rather than listed, so the budget can
+/// be as large as `i64` allows.
+pub fn sample_race(ways: i64, time_budget: i64, rng: &mut SplitMix64) -> Option<Race> {
+    let (first, step) = design_times(ways);
+    if ways < 0 || time_budget < first {
+        return None;
+    }
+    let time_at = |idx: i64| first + step * idx;
+    design_for(ways, first)?;
+    // Records only grow with the allotted time, so the designs that fit in
+    // `i64` run up to some last index, found here by binary search.
+    let (mut lo, mut hi) = (0, (time_budget - first) / step);
+    while lo < hi {
+        let mid = hi - (hi - lo) / 2;
+        match design_for(ways, time_at(mid)) {
+            Some(_) => lo = mid,
+            None => hi = mid - 1,
+        }
+    }
+    let design = design_for(ways, time_at(rng.in_range(&(0..=lo))))?;
+    Some(Race {
+        allotted_time: design.allotted_time,
+        record_distance: rng.in_range(&design.records),
+    })
+}
+
+/// A generated race sheet and the answers the puzzle should give for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedSheet {
+    pub races: Vec<Race>,
+    pub text: String,
+    pub part_1: String,
+    pub part_2: String,
+}
+
+fn kerned_ways_to_win<N: RaceInt>(text: &str) -> Result<String, Box<dyn Error>> {
+    let race = parse_kerned_race::<N>(text)?;
+    Ok(checked_ways_to_win_race(&race)?.to_string())
+}
+
+/// Builds a sheet with one race per entry of `ways`, each sampled with
+/// `sample_race`.
+pub fn generate_sheet(ways: &[i64], time_budget: i64, seed: u64) -> Result<GeneratedSheet, String> {
+    let mut rng = SplitMix64::new(seed);
+    let races = ways
+        .iter()
+        .map(|w| {
+            sample_race(*w, time_budget, &mut rng).ok_or(format!(
+                "no race has {} ways to win within time {}",
+                w, time_budget
+            ))
+        })
+        .collect::<Result<Vec<Race>, String>>()?;
+    let text = write_race_sheet(&races);
+    let part_1 = ways
+        .iter()
+        .fold(BigInt::from(1), |acc, w| acc * w)
+        .to_string();
+    let part_2 = widening(
+        || kerned_ways_to_win::<i64>(&text),
+        || kerned_ways_to_win::<i128>(&text),
+        || kerned_ways_to_win::<BigInt>(&text),
+    )
+    .map_err(|err| format!("the kerned race can't be solved: {}", err))?;
+    Ok(GeneratedSheet {
+        races,
+        text,
+        part_1,
+        part_2,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::race::{ways_to_win_race, ways_to_win_race_brute_force};
+    use crate::sheet::parse_race_sheet;
+
+    #[test]
+    fn test_designs_give_wanted_ways() {
+        for ways in 0..12 {
+            for design in race_designs_within(ways, 40) {
+                for record_distance in design.records.clone() {
+                    let race = Race {
+                        allotted_time: design.allotted_time,
+                        record_distance,
+                    };
+                    assert_eq!(ways_to_win_race_brute_force(&race), ways, "{:?}", race);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_designs_are_complete() {
+        for allotted_time in 0..30 {
+            for record_distance in 0..(allotted_time * allotted_time / 4) {
+                let race = Race {
+                    allotted_time,
+                    record_distance,
+                };
+                let ways = ways_to_win_race(&race);
+                assert!(race_designs_within(ways, 30)
+                    .any(|d| d.allotted_time == allotted_time
+                        && d.records.contains(&record_distance)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_sample_race_matches_listed_designs() {
+        for ways in 0..6 {
+            let designs: Vec<RaceDesign> = race_designs_within(ways, 50).collect();
+            let mut rng = SplitMix64::new(ways as u64);
+            let mut listed_rng = rng.clone();
+            let race = sample_race(ways, 50, &mut rng).unwrap();
+            let last = designs.len() as i64 - 1;
+            let design = &designs[listed_rng.in_range(&(0..=last)) as usize];
+            assert_eq!(race.allotted_time, design.allotted_time);
+            assert_eq!(race.record_distance, listed_rng.in_range(&design.records));
+        }
+    }
+
+    #[test]
+    fn test_sample_race_huge_budget() {
+        for ways in [0, 3, 1_000_000] {
+            let race = sample_race(ways, i64::MAX, &mut SplitMix64::new(5)).unwrap();
+            assert_eq!(
+                checked_ways_to_win_race(&race.widen::<i128>()),
+                Ok(ways as i128)
+            );
+        }
+    }
+
+    #[test]
+    fn test_no_design_within_budget() {
+        assert_eq!(race_designs_within(10, 10).count(), 0);
+        assert!(sample_race(10, 10, &mut SplitMix64::new(1)).is_none());
+    }
+
+    #[test]
+    fn test_generate_sheet() {
+        let generated = generate_sheet(&[4, 8, 9], 60, 7).unwrap();
+        assert_eq!(generated, generate_sheet(&[4, 8, 9], 60, 7).unwrap());
+        let sheet = parse_race_sheet(&generated.text).unwrap();
+        assert_eq!(sheet.races, generated.races);
+        let ways: Vec<i64> = sheet.races.iter().map(ways_to_win_race).collect();
+        assert_eq!(ways, vec![4, 8, 9]);
+        assert_eq!(generated.part_1, "288");
+        let kerned: Race = parse_kerned_race(&generated.text).unwrap();
+        assert_eq!(generated.part_2, ways_to_win_race(&kerned).to_string());
+    }
+}
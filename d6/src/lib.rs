@@ -0,0 +1,6 @@
+pub mod analysis;
+pub mod boat;
+pub mod design;
+pub mod plot;
+pub mod race;
+pub mod sheet;
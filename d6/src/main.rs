@@ -1,67 +1,210 @@
-use std::fs;
+use d6::boat::{product_of_ways_to_win, Boat};
+use d6::design::generate_sheet;
+use d6::plot::{render_ascii, render_svg};
+use d6::race::{widening, Race, RaceInt};
+use d6::sheet::{parse_kerned_race, parse_race_sheet};
+use num_bigint::BigInt;
+use std::error::Error;
+use std::{env, fs, process};
 
-struct Race {
-    allotted_time: i64,
-    record_distance: i64,
+fn print_answer(part: usize, answer: Result<String, Box<dyn Error>>) {
+    match answer {
+        Ok(answer) => println!("Part {}: {}", part, answer),
+        Err(err) => {
+            eprintln!("Part {}: {}", part, err);
+            process::exit(1);
+        }
+    }
 }
 
-fn can_win_race(race: &Race, hold_time: i64) -> bool {
-    assert!(hold_time <= race.allotted_time);
-    let travel_time = race.allotted_time - hold_time;
-    let distance_traveled = travel_time * hold_time;
-    distance_traveled > race.record_distance
+fn product_of_ways<N: RaceInt>(races: &[Race], boat: &Boat) -> Result<String, Box<dyn Error>> {
+    Ok(product_of_ways_to_win::<N>(boat.model(), races)?.to_string())
 }
 
-fn ways_to_win_race(race: &Race) -> i64 {
-    (0..=race.allotted_time)
-        .map(|hold_time| can_win_race(race, hold_time) as i64)
-        .sum()
+/// Multiplies the races' ways to win in `i64` first, widening to `i128` and
+/// then to an arbitrary-size integer whenever they don't fit.
+fn part_1(races: &[Race], boat: &Boat) {
+    let answer = widening(
+        || product_of_ways::<i64>(races, boat),
+        || product_of_ways::<i128>(races, boat),
+        || product_of_ways::<BigInt>(races, boat),
+    );
+    print_answer(1, answer);
 }
 
-fn part_1(races: &Vec<Race>) {
-    let ways_to_win_each = races.iter().map(ways_to_win_race);
-    let answer = ways_to_win_each.fold(1, |acc, next| acc * next);
-    println!("Part 1: {}", answer);
+fn kerned_ways_to_win<N: RaceInt>(contents: &str, boat: &Boat) -> Result<String, Box<dyn Error>> {
+    let big_race = parse_kerned_race::<N>(contents)?;
+    Ok(boat.model().ways_to_win(&big_race)?.to_string())
 }
 
-fn part_2(races: &Vec<Race>) {
-    let badly_kerned_race_time: String =
-        races.iter().map(|r| r.allotted_time.to_string()).collect();
-    let badly_kerned_race_distance: String = races
-        .iter()
-        .map(|r| r.record_distance.to_string())
-        .collect();
-    let big_race = Race {
-        allotted_time: badly_kerned_race_time.parse().unwrap(),
-        record_distance: badly_kerned_race_distance.parse().unwrap(),
+/// Tries the kerned race in `i64` first, widening to `i128` and then to an
+/// arbitrary-size integer whenever it doesn't fit.
+fn part_2(contents: &str, boat: &Boat) {
+    let answer = widening(
+        || kerned_ways_to_win::<i64>(contents, boat),
+        || kerned_ways_to_win::<i128>(contents, boat),
+        || kerned_ways_to_win::<BigInt>(contents, boat),
+    );
+    print_answer(2, answer);
+}
+
+fn race_analysis<N: RaceInt>(race: &Race, boat: &Boat) -> Result<String, Box<dyn Error>> {
+    Ok(race.widen::<N>().analysis(boat.model())?.to_string())
+}
+
+fn kerned_analysis<N: RaceInt>(contents: &str, boat: &Boat) -> Result<String, Box<dyn Error>> {
+    let big_race = parse_kerned_race::<N>(contents)?;
+    Ok(big_race.analysis(boat.model())?.to_string())
+}
+
+/// Reports on each race and then the kerned race, widening each in turn
+/// like the answers do.
+fn print_reports(contents: &str, races: &[Race], boat: &Boat) {
+    for (idx, race) in races.iter().enumerate() {
+        println!(
+            "Race {} (time {}, record {}):",
+            idx + 1,
+            race.allotted_time,
+            race.record_distance
+        );
+        let report = widening(
+            || race_analysis::<i64>(race, boat),
+            || race_analysis::<i128>(race, boat),
+            || race_analysis::<BigInt>(race, boat),
+        );
+        match report {
+            Ok(report) => print!("{}", report),
+            Err(err) => println!("  {}", err),
+        }
+    }
+    println!("Kerned race:");
+    let report = widening(
+        || kerned_analysis::<i64>(contents, boat),
+        || kerned_analysis::<i128>(contents, boat),
+        || kerned_analysis::<BigInt>(contents, boat),
+    );
+    match report {
+        Ok(report) => print!("{}", report),
+        Err(err) => println!("  {}", err),
+    }
+}
+
+const PLOT_WIDTH: usize = 72;
+const PLOT_HEIGHT: usize = 16;
+
+fn arg_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|idx| args.get(idx + 1))
+        .map(|value| value.as_str())
+}
+
+/// Plots the race picked with `--race N` (counting from 1) or `--race kerned`,
+/// or every race if none is picked. `--svg out.svg` writes the picked race
+/// (the first, by default) to an SVG file instead.
+fn plot(
+    args: &[String],
+    contents: &str,
+    races: &[Race],
+    boat: &Boat,
+) -> Result<(), Box<dyn Error>> {
+    let picked: Vec<(String, Race)> = match arg_value(args, "--race") {
+        Some("kerned") => vec![("Kerned race".to_string(), parse_kerned_race(contents)?)],
+        Some(n) => {
+            let idx: usize = n.parse()?;
+            let race = idx
+                .checked_sub(1)
+                .and_then(|i| races.get(i))
+                .ok_or(format!("no race {}", n))?;
+            vec![(format!("Race {}", idx), race.clone())]
+        }
+        None => races
+            .iter()
+            .enumerate()
+            .map(|(idx, race)| (format!("Race {}", idx + 1), race.clone()))
+            .collect(),
+    };
+    if let Some(path) = arg_value(args, "--svg") {
+        let (name, race) = picked.first().ok_or("no races to plot")?;
+        fs::write(path, render_svg(race, boat.model())?)?;
+        println!("Wrote {} to {}", name, path);
+        return Ok(());
+    }
+    for (name, race) in picked {
+        println!("{}:", name);
+        print!(
+            "{}",
+            render_ascii(&race, boat.model(), PLOT_WIDTH, PLOT_HEIGHT)?
+        );
+    }
+    Ok(())
+}
+
+const DEFAULT_TIME_BUDGET: i64 = 100;
+
+/// Generates a sheet for `--generate 4,8,9`, with races no longer than
+/// `--max-time` and sampled from `--seed`. The sheet goes to `--out` if
+/// given, otherwise to stdout with its answers on stderr.
+fn generate(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let ways = arg_value(args, "--generate")
+        .ok_or("--generate needs a comma-separated list of ways to win")?
+        .split(',')
+        .map(|w| w.trim().parse::<i64>())
+        .collect::<Result<Vec<i64>, _>>()?;
+    let time_budget = match arg_value(args, "--max-time") {
+        Some(value) => value.parse()?,
+        None => DEFAULT_TIME_BUDGET,
     };
-    let answer = ways_to_win_race(&big_race);
-    println!("Part 2: {}", answer);
+    let seed = match arg_value(args, "--seed") {
+        Some(value) => value.parse()?,
+        None => 0,
+    };
+    let generated = generate_sheet(&ways, time_budget, seed)?;
+    match arg_value(args, "--out") {
+        Some(path) => {
+            fs::write(path, &generated.text)?;
+            println!("Wrote {}", path);
+            println!("Part 1: {}", generated.part_1);
+            println!("Part 2: {}", generated.part_2);
+        }
+        None => {
+            print!("{}", generated.text);
+            eprintln!("Part 1: {}", generated.part_1);
+            eprintln!("Part 2: {}", generated.part_2);
+        }
+    }
+    Ok(())
 }
 
 fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.iter().any(|arg| arg == "--generate") {
+        if let Err(err) = generate(&args) {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+        return;
+    }
     let contents = fs::read_to_string("./input.txt").unwrap();
-    let mut lines = contents.lines();
-    let durations: Vec<_> = lines
-        .next()
-        .unwrap()
-        .split_ascii_whitespace()
-        .skip(1)
-        .filter_map(|x| x.parse::<i64>().ok())
-        .collect();
-    let records: Vec<_> = lines
-        .next()
-        .unwrap()
-        .split_ascii_whitespace()
-        .skip(1)
-        .filter_map(|x| x.parse::<i64>().ok())
-        .collect();
-    let races: Vec<Race> = (0..durations.len())
-        .map(|idx| Race {
-            allotted_time: durations[idx],
-            record_distance: records[idx],
-        })
-        .collect();
-    part_1(&races);
-    part_2(&races);
+    let sheet = match parse_race_sheet(&contents) {
+        Ok(sheet) => sheet,
+        Err(err) => {
+            eprintln!("input.txt: {}", err);
+            process::exit(1);
+        }
+    };
+    let boat = sheet.boat.unwrap_or_default();
+    if args.iter().any(|arg| arg == "--plot" || arg == "--svg") {
+        if let Err(err) = plot(&args, &contents, &sheet.races, &boat) {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+        return;
+    }
+    if args.iter().any(|arg| arg == "--report") {
+        print_reports(&contents, &sheet.races, &boat);
+        return;
+    }
+    part_1(&sheet.races, &boat);
+    part_2(&contents, &boat);
 }
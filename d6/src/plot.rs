@@ -0,0 +1,235 @@
+use crate::boat::BoatModel;
+use crate::race::{Race, RaceError};
+
+const SVG_WIDTH: f64 = 640.0;
+const SVG_HEIGHT: f64 = 360.0;
+const SVG_MARGIN: f64 = 40.0;
+const SVG_SAMPLES: i64 = 400;
+
+/// Hold times to plot, one per column. Races shorter than `columns` get a
+/// column per hold time; longer ones are sampled evenly.
+fn sample_hold_times(allotted_time: i64, columns: i64) -> Vec<i64> {
+    let columns = columns.clamp(1, allotted_time.max(0) + 1);
+    if columns == 1 {
+        return vec![0];
+    }
+    (0..columns)
+        .map(|c| (c as i128 * allotted_time as i128 / (columns - 1) as i128) as i64)
+        .collect()
+}
+
+fn top_distance(race: &Race, model: &dyn BoatModel) -> Result<f64, RaceError> {
+    let peak = model.peak_hold_time(&race.allotted_time)?;
+    let best = model.distance(&race.allotted_time, &peak)?;
+    Ok((best.max(race.record_distance).max(1)) as f64)
+}
+
+/// Draws the distance curve as `*`, the record as `-` and the winning
+/// region between them as `#`, with hold time running left to right. The
+/// axis marks every winning column with `^`, even where the curve is too
+/// close to the record to leave room for shading. The plot is always at
+/// least one row high and one column wide.
+pub fn render_ascii(
+    race: &Race,
+    model: &dyn BoatModel,
+    width: usize,
+    height: usize,
+) -> Result<String, RaceError> {
+    let height = height.max(1);
+    let hold_times = sample_hold_times(race.allotted_time, width as i64);
+    let winners = model.winning_hold_times(race)?;
+    let top = top_distance(race, model)?;
+    let row_of = |distance: i64| {
+        let scaled = distance.max(0) as f64 / top * (height - 1) as f64;
+        (height - 1) - scaled.round() as usize
+    };
+    let record_row = row_of(race.record_distance);
+    let mut grid = vec![vec![' '; hold_times.len()]; height];
+    let mut axis = vec!['-'; hold_times.len()];
+    for (col, hold_time) in hold_times.iter().enumerate() {
+        let curve_row = row_of(model.distance(&race.allotted_time, hold_time)?);
+        let wins = winners.as_ref().is_some_and(|w| w.contains(hold_time));
+        if race.record_distance >= 0 {
+            grid[record_row][col] = '-';
+        }
+        if wins {
+            axis[col] = '^';
+            for row in grid.iter_mut().take(record_row + 1).skip(curve_row) {
+                row[col] = '#';
+            }
+        }
+        grid[curve_row][col] = '*';
+    }
+    let mut out = String::new();
+    for row in grid {
+        out.push('|');
+        out.extend(row);
+        out.push('\n');
+    }
+    out.push('+');
+    out.extend(axis);
+    out.push('\n');
+    out.push_str(&format!(
+        "hold time 0..={}, record {}, ",
+        race.allotted_time, race.record_distance
+    ));
+    match winners {
+        Some(w) => out.push_str(&format!(
+            "winning hold times [{}, {}] ({} ways)\n",
+            w.start(),
+            w.end(),
+            w.end() - w.start() + 1
+        )),
+        None => out.push_str("no winning hold times\n"),
+    }
+    Ok(out)
+}
+
+/// The same picture as `render_ascii`, as a standalone SVG document.
+pub fn render_svg(race: &Race, model: &dyn BoatModel) -> Result<String, RaceError> {
+    let time = race.allotted_time.max(1) as f64;
+    let top = top_distance(race, model)?;
+    let x = |hold_time: i64| SVG_MARGIN + hold_time as f64 / time * (SVG_WIDTH - 2.0 * SVG_MARGIN);
+    let y = |distance: i64| {
+        SVG_HEIGHT - SVG_MARGIN - distance.max(0) as f64 / top * (SVG_HEIGHT - 2.0 * SVG_MARGIN)
+    };
+    let point = |h: i64| -> Result<String, RaceError> {
+        Ok(format!(
+            "{:.2},{:.2}",
+            x(h),
+            y(model.distance(&race.allotted_time, &h)?)
+        ))
+    };
+    let curve = sample_hold_times(race.allotted_time, SVG_SAMPLES)
+        .into_iter()
+        .map(point)
+        .collect::<Result<Vec<String>, RaceError>>()?;
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+        w = SVG_WIDTH,
+        h = SVG_HEIGHT
+    );
+    if let Some(winners) = model.winning_hold_times(race)? {
+        let (lo, hi) = (*winners.start(), *winners.end());
+        let mut region = sample_hold_times(hi - lo, SVG_SAMPLES)
+            .into_iter()
+            .map(|offset| point(lo + offset))
+            .collect::<Result<Vec<String>, RaceError>>()?;
+        region.push(format!("{:.2},{:.2}", x(hi), y(race.record_distance)));
+        region.push(format!("{:.2},{:.2}", x(lo), y(race.record_distance)));
+        out.push_str(&format!(
+            "  <polygon class=\"winning\" points=\"{}\" fill=\"#9be39b\" />\n",
+            region.join(" ")
+        ));
+    }
+    out.push_str(&format!(
+        "  <line class=\"axis\" x1=\"{m}\" y1=\"{b}\" x2=\"{r}\" y2=\"{b}\" stroke=\"black\" />\n",
+        m = SVG_MARGIN,
+        b = SVG_HEIGHT - SVG_MARGIN,
+        r = SVG_WIDTH - SVG_MARGIN
+    ));
+    out.push_str(&format!(
+        "  <line class=\"record\" x1=\"{:.2}\" y1=\"{y:.2}\" x2=\"{:.2}\" y2=\"{y:.2}\" stroke=\"red\" stroke-dasharray=\"4\" />\n",
+        x(0),
+        x(race.allotted_time),
+        y = y(race.record_distance)
+    ));
+    out.push_str(&format!(
+        "  <polyline class=\"distance\" points=\"{}\" fill=\"none\" stroke=\"blue\" />\n",
+        curve.join(" ")
+    ));
+    out.push_str(&format!(
+        "  <text x=\"{}\" y=\"{}\">time {}, record {}</text>\n",
+        SVG_MARGIN,
+        SVG_MARGIN / 2.0,
+        race.allotted_time,
+        race.record_distance
+    ));
+    out.push_str("</svg>\n");
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boat::{LinearCharge, SpeedCap};
+    use crate::race::ways_to_win_race;
+
+    #[test]
+    fn test_render_ascii_small_race() {
+        let race = Race {
+            allotted_time: 7,
+            record_distance: 9,
+        };
+        let plot = render_ascii(&race, &LinearCharge, 80, 7).unwrap();
+        let rows: Vec<&str> = plot.lines().collect();
+        assert_eq!(rows[0], "|   **   ");
+        assert_eq!(rows[7], "+--^^^^--");
+        let winning_columns = rows[7].matches('^').count();
+        assert_eq!(winning_columns as i64, ways_to_win_race(&race));
+        assert!(plot.ends_with("winning hold times [2, 5] (4 ways)\n"));
+    }
+
+    #[test]
+    fn test_render_ascii_samples_long_race() {
+        let race = Race {
+            allotted_time: 71530,
+            record_distance: 940200,
+        };
+        let plot = render_ascii(&race, &LinearCharge, 60, 10).unwrap();
+        let rows: Vec<&str> = plot.lines().collect();
+        assert!(rows[..rows.len() - 1]
+            .iter()
+            .all(|row| row.chars().count() == 61));
+        assert!(plot.ends_with("(71503 ways)\n"));
+    }
+
+    #[test]
+    fn test_render_ascii_degenerate_sizes() {
+        let race = Race {
+            allotted_time: 7,
+            record_distance: 9,
+        };
+        let flat = render_ascii(&race, &LinearCharge, 80, 0).unwrap();
+        let rows: Vec<&str> = flat.lines().collect();
+        assert_eq!(rows[0], "|********");
+        assert_eq!(rows[1], "+--^^^^--");
+        let narrow = render_ascii(&race, &LinearCharge, 0, 0).unwrap();
+        assert_eq!(narrow.lines().nth(1), Some("+-"));
+    }
+
+    #[test]
+    fn test_render_ascii_other_model() {
+        let race = Race {
+            allotted_time: 7,
+            record_distance: 5,
+        };
+        let model = SpeedCap::new(2).unwrap();
+        let plot = render_ascii(&race, &model, 80, 7).unwrap();
+        let axis = plot.lines().nth(7).unwrap();
+        assert_eq!(axis, "+-^^^^---");
+        assert_eq!(
+            axis.matches('^').count() as i64,
+            model.ways_to_win(&race).unwrap()
+        );
+        assert!(plot.ends_with("winning hold times [1, 4] (4 ways)\n"));
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let race = Race {
+            allotted_time: 30,
+            record_distance: 200,
+        };
+        let svg = render_svg(&race, &LinearCharge).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("class=\"winning\""));
+        let losing = Race {
+            allotted_time: 10,
+            record_distance: 25,
+        };
+        assert!(!render_svg(&losing, &LinearCharge)
+            .unwrap()
+            .contains("class=\"winning\""));
+    }
+}
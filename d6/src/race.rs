@@ -0,0 +1,321 @@
+use crate::boat::{BoatModel, LinearCharge};
+use crate::sheet::SheetError;
+use num_integer::{Integer, Roots};
+use num_traits::{CheckedAdd, CheckedMul, CheckedSub};
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// The integer types a race can be measured in. `i64` covers the puzzle
+/// input, `i128` the larger kerned races, and `num_bigint::BigInt` anything
+/// beyond that. Each can hold any `i64`, so narrower races can be widened.
+pub trait RaceInt:
+    Integer
+    + Roots
+    + CheckedAdd
+    + CheckedSub
+    + CheckedMul
+    + Clone
+    + Debug
+    + Display
+    + FromStr
+    + From<i64>
+{
+}
+
+impl<N> RaceInt for N where
+    N: Integer
+        + Roots
+        + CheckedAdd
+        + CheckedSub
+        + CheckedMul
+        + Clone
+        + Debug
+        + Display
+        + FromStr
+        + From<i64>
+{
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Race<N = i64> {
+    pub allotted_time: N,
+    pub record_distance: N,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RaceError {
+    /// Some intermediate value didn't fit in the race's integer type.
+    Overflow,
+    /// A number couldn't be read as the race's integer type.
+    InvalidNumber(String),
+}
+
+impl Display for RaceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RaceError::Overflow => write!(f, "race arithmetic overflowed"),
+            RaceError::InvalidNumber(value) => write!(f, "invalid race number: {}", value),
+        }
+    }
+}
+
+impl Error for RaceError {}
+
+/// Errors that can mean a race didn't fit the integer type it was measured
+/// in, so the same work in a wider type might succeed.
+pub trait DidNotFit {
+    fn did_not_fit(&self) -> bool;
+}
+
+impl DidNotFit for RaceError {
+    fn did_not_fit(&self) -> bool {
+        matches!(self, RaceError::Overflow)
+    }
+}
+
+impl DidNotFit for Box<dyn Error> {
+    fn did_not_fit(&self) -> bool {
+        self.downcast_ref::<RaceError>()
+            .is_some_and(RaceError::did_not_fit)
+            || self
+                .downcast_ref::<SheetError>()
+                .is_some_and(SheetError::did_not_fit)
+    }
+}
+
+/// Runs the same work in `i64`, then `i128`, then `BigInt`, moving to the
+/// wider type only when the narrower one didn't fit. Any other error is
+/// returned straight away.
+pub fn widening<T, E: DidNotFit>(
+    narrow: impl FnOnce() -> Result<T, E>,
+    wide: impl FnOnce() -> Result<T, E>,
+    big: impl FnOnce() -> Result<T, E>,
+) -> Result<T, E> {
+    match narrow() {
+        Err(err) if err.did_not_fit() => {}
+        result => return result,
+    }
+    match wide() {
+        Err(err) if err.did_not_fit() => big(),
+        result => result,
+    }
+}
+
+impl Race {
+    /// The same race measured in a wider integer type.
+    pub fn widen<N: RaceInt>(&self) -> Race<N> {
+        Race {
+            allotted_time: N::from(self.allotted_time),
+            record_distance: N::from(self.record_distance),
+        }
+    }
+}
+
+fn checked<N>(value: Option<N>) -> Result<N, RaceError> {
+    value.ok_or(RaceError::Overflow)
+}
+
+/// Unwraps an `i64` result for races known to fit, panicking rather than
+/// returning a wrong answer.
+fn expect_fit<T>(race: &Race, result: Result<T, RaceError>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(err) => panic!("{:?}: {}", race, err),
+    }
+}
+
+pub fn can_win_race(race: &Race, hold_time: i64) -> bool {
+    expect_fit(race, LinearCharge.can_win(race, &hold_time))
+}
+
+pub fn checked_can_win_race<N: RaceInt>(race: &Race<N>, hold_time: &N) -> Result<bool, RaceError> {
+    if *hold_time < N::zero() || *hold_time > race.allotted_time {
+        return Ok(false);
+    }
+    let travel_time = checked(race.allotted_time.checked_sub(hold_time))?;
+    let distance_traveled = checked(travel_time.checked_mul(hold_time))?;
+    Ok(distance_traveled > race.record_distance)
+}
+
+/// The hold times that beat the record. `h * (T - h) > D` is a downward
+/// parabola symmetric about `T / 2`, so the winners form one interval
+/// `[lo, T - lo]`. `lo` is first estimated from the integer square root of
+/// the discriminant `T^2 - 4D`, then nudged onto the exact boundary.
+pub fn checked_winning_hold_times<N: RaceInt>(
+    race: &Race<N>,
+) -> Result<Option<RangeInclusive<N>>, RaceError> {
+    let (zero, one) = (N::zero(), N::one());
+    let two = one.clone() + one.clone();
+    let time = &race.allotted_time;
+    if *time < zero {
+        return Ok(None);
+    }
+    let four_record = checked(
+        race.record_distance
+            .checked_mul(&(two.clone() * two.clone())),
+    )?;
+    let discriminant = checked(checked(time.checked_mul(time))?.checked_sub(&four_record))?;
+    if discriminant < zero {
+        return Ok(None);
+    }
+    let half_time = time.clone() / two.clone();
+    let mut lo = ((time.clone() - discriminant.sqrt()) / two).max(zero.clone());
+    while lo > zero && checked_can_win_race(race, &(lo.clone() - one.clone()))? {
+        lo = lo - one.clone();
+    }
+    while lo <= half_time && !checked_can_win_race(race, &lo)? {
+        lo = lo + one.clone();
+    }
+    match lo <= half_time {
+        true => Ok(Some(lo.clone()..=(time.clone() - lo))),
+        false => Ok(None),
+    }
+}
+
+/// The `i64` closed form, for races known to fit. Panics on overflow rather
+/// than returning a wrong count.
+pub fn winning_hold_times(race: &Race) -> Option<RangeInclusive<i64>> {
+    expect_fit(race, checked_winning_hold_times(race))
+}
+
+pub fn checked_ways_to_win_race<N: RaceInt>(race: &Race<N>) -> Result<N, RaceError> {
+    match checked_winning_hold_times(race)? {
+        Some(winners) => {
+            let (lo, hi) = winners.into_inner();
+            checked((hi - lo).checked_add(&N::one()))
+        }
+        None => Ok(N::zero()),
+    }
+}
+
+pub fn ways_to_win_race(race: &Race) -> i64 {
+    expect_fit(race, LinearCharge.ways_to_win(race))
+}
+
+pub fn ways_to_win_race_brute_force(race: &Race) -> i64 {
+    (0..=race.allotted_time)
+        .map(|hold_time| can_win_race(race, hold_time) as i64)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigInt;
+
+    #[test]
+    fn test_ways_to_win_test_races() {
+        let races = [(7, 9, 4), (15, 40, 8), (30, 200, 9), (71530, 940200, 71503)];
+        for (allotted_time, record_distance, ways) in races {
+            let race = Race {
+                allotted_time,
+                record_distance,
+            };
+            assert_eq!(ways_to_win_race(&race), ways);
+        }
+    }
+
+    #[test]
+    fn test_no_way_to_win() {
+        let race = Race {
+            allotted_time: 10,
+            record_distance: 30,
+        };
+        assert_eq!(winning_hold_times(&race), None);
+        assert_eq!(ways_to_win_race(&race), 0);
+    }
+
+    #[test]
+    fn test_tie_with_record_does_not_win() {
+        let race = Race {
+            allotted_time: 10,
+            record_distance: 25,
+        };
+        assert_eq!(ways_to_win_race(&race), 0);
+        let race = Race {
+            allotted_time: 10,
+            record_distance: 24,
+        };
+        assert_eq!(winning_hold_times(&race), Some(5..=5));
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        for allotted_time in 0..60 {
+            for record_distance in -5..(allotted_time * allotted_time / 4 + 5) {
+                let race = Race {
+                    allotted_time,
+                    record_distance,
+                };
+                assert_eq!(
+                    ways_to_win_race(&race),
+                    ways_to_win_race_brute_force(&race),
+                    "{:?}",
+                    race
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_generic_widths_agree() {
+        for allotted_time in 0..40 {
+            for record_distance in -5..(allotted_time * allotted_time / 4 + 5) {
+                let race = Race {
+                    allotted_time,
+                    record_distance,
+                };
+                let wide = Race {
+                    allotted_time: allotted_time as i128,
+                    record_distance: record_distance as i128,
+                };
+                let big = Race {
+                    allotted_time: BigInt::from(allotted_time),
+                    record_distance: BigInt::from(record_distance),
+                };
+                let ways = ways_to_win_race_brute_force(&race);
+                assert_eq!(checked_ways_to_win_race(&race), Ok(ways));
+                assert_eq!(checked_ways_to_win_race(&wide), Ok(ways as i128));
+                assert_eq!(checked_ways_to_win_race(&big), Ok(BigInt::from(ways)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_overflow_is_reported() {
+        let race = Race {
+            allotted_time: 4_000_000_000_i64,
+            record_distance: 1_000_000_000,
+        };
+        assert_eq!(checked_ways_to_win_race(&race), Err(RaceError::Overflow));
+        let wide = Race {
+            allotted_time: 4_000_000_000_i128,
+            record_distance: 1_000_000_000,
+        };
+        let big = Race {
+            allotted_time: BigInt::from(4_000_000_000_i64),
+            record_distance: BigInt::from(1_000_000_000),
+        };
+        let ways = checked_ways_to_win_race(&wide).unwrap();
+        assert_eq!(BigInt::from(ways), checked_ways_to_win_race(&big).unwrap());
+        assert_eq!(ways, 4_000_000_000 - 1);
+    }
+
+    #[test]
+    fn test_widening_only_retries_overflow() {
+        let overflowed = || Err::<i32, RaceError>(RaceError::Overflow);
+        assert_eq!(widening(overflowed, overflowed, || Ok(3)), Ok(3));
+        assert_eq!(widening(overflowed, || Ok(2), || Ok(3)), Ok(2));
+        let invalid = RaceError::InvalidNumber("x".to_string());
+        assert_eq!(
+            widening(|| Err(invalid.clone()), || Ok(2), || Ok(3)),
+            Err(invalid)
+        );
+        assert_eq!(
+            widening(overflowed, overflowed, overflowed),
+            Err(RaceError::Overflow)
+        );
+    }
+}
@@ -0,0 +1,402 @@
+use crate::boat::{parse_boat_model, Boat};
+use crate::race::{DidNotFit, Race, RaceInt};
+use std::error::Error;
+use std::fmt::{self, Display};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SheetErrorKind {
+    MissingRow(&'static str),
+    DuplicateRow(String),
+    UnknownLabel(String),
+    MissingColon,
+    InvalidNumber(String),
+    /// A kerned number too large for the integer type it was read as.
+    OutOfRange(String),
+    ColumnCountMismatch {
+        times: usize,
+        distances: usize,
+    },
+    InvalidBoat(String),
+}
+
+/// Where on the sheet something went wrong. Lines and columns count from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SheetError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: SheetErrorKind,
+}
+
+impl Display for SheetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match &self.kind {
+            SheetErrorKind::MissingRow(label) => write!(f, "missing {} row", label),
+            SheetErrorKind::DuplicateRow(label) => write!(f, "duplicate {} row", label),
+            SheetErrorKind::UnknownLabel(label) => write!(f, "unknown row label {:?}", label),
+            SheetErrorKind::MissingColon => write!(f, "expected a label followed by ':'"),
+            SheetErrorKind::InvalidNumber(token) => write!(f, "invalid number {:?}", token),
+            SheetErrorKind::OutOfRange(digits) => write!(f, "{} is out of range", digits),
+            SheetErrorKind::ColumnCountMismatch { times, distances } => {
+                write!(f, "{} times but {} distances", times, distances)
+            }
+            SheetErrorKind::InvalidBoat(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl Error for SheetError {}
+
+impl DidNotFit for SheetError {
+    fn did_not_fit(&self) -> bool {
+        matches!(self.kind, SheetErrorKind::OutOfRange(_))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Token<'a> {
+    text: &'a str,
+    column: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Row<'a> {
+    line: usize,
+    /// The column just past the end of the line, for errors about things
+    /// that are missing from it.
+    end_column: usize,
+    tokens: Vec<Token<'a>>,
+}
+
+impl<'a> Row<'a> {
+    fn numbers(&self) -> Result<Vec<i64>, SheetError> {
+        self.tokens
+            .iter()
+            .map(|token| {
+                token.text.parse::<i64>().map_err(|_| SheetError {
+                    line: self.line,
+                    column: token.column,
+                    kind: SheetErrorKind::InvalidNumber(token.text.to_string()),
+                })
+            })
+            .collect()
+    }
+
+    /// The row's numbers with the spaces between them ignored.
+    fn kerned<N: RaceInt>(&self) -> Result<N, SheetError> {
+        for token in &self.tokens {
+            if !token.text.chars().all(|c| c.is_ascii_digit()) {
+                return Err(SheetError {
+                    line: self.line,
+                    column: token.column,
+                    kind: SheetErrorKind::InvalidNumber(token.text.to_string()),
+                });
+            }
+        }
+        let digits: String = self.tokens.iter().map(|token| token.text).collect();
+        let column = self.tokens.first().map_or(self.end_column, |t| t.column);
+        if digits.is_empty() {
+            return Err(SheetError {
+                line: self.line,
+                column,
+                kind: SheetErrorKind::InvalidNumber(digits),
+            });
+        }
+        digits.parse::<N>().map_err(|_| SheetError {
+            line: self.line,
+            column,
+            kind: SheetErrorKind::OutOfRange(digits.clone()),
+        })
+    }
+}
+
+fn tokenize(text: &str, offset: usize) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut start: Option<usize> = None;
+    for (idx, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some(idx),
+            (Some(from), true) => {
+                tokens.push(Token {
+                    text: &text[from..idx],
+                    column: offset + text[..from].chars().count() + 1,
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    tokens
+}
+
+#[derive(Debug, Default)]
+struct Rows<'a> {
+    time: Option<Row<'a>>,
+    distance: Option<Row<'a>>,
+    boat: Option<Row<'a>>,
+    last_line: usize,
+}
+
+fn read_rows(text: &str) -> Result<Rows<'_>, SheetError> {
+    let mut rows = Rows::default();
+    for (idx, line) in text.lines().enumerate() {
+        let line_number = idx + 1;
+        rows.last_line = line_number;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let label_column = line.chars().take_while(|c| c.is_whitespace()).count() + 1;
+        let (label, rest) = match line.split_once(':') {
+            Some(split) => split,
+            None => {
+                return Err(SheetError {
+                    line: line_number,
+                    column: label_column,
+                    kind: SheetErrorKind::MissingColon,
+                })
+            }
+        };
+        let label = label.trim();
+        let slot = match label {
+            "Time" => &mut rows.time,
+            "Distance" => &mut rows.distance,
+            "Boat" => &mut rows.boat,
+            _ => {
+                return Err(SheetError {
+                    line: line_number,
+                    column: label_column,
+                    kind: SheetErrorKind::UnknownLabel(label.to_string()),
+                })
+            }
+        };
+        if slot.is_some() {
+            return Err(SheetError {
+                line: line_number,
+                column: label_column,
+                kind: SheetErrorKind::DuplicateRow(label.to_string()),
+            });
+        }
+        let offset = line[..line.len() - rest.len()].chars().count();
+        *slot = Some(Row {
+            line: line_number,
+            end_column: line.chars().count() + 1,
+            tokens: tokenize(rest, offset),
+        });
+    }
+    Ok(rows)
+}
+
+impl<'a> Rows<'a> {
+    fn require(
+        &self,
+        row: &'a Option<Row>,
+        label: &'static str,
+    ) -> Result<&'a Row<'a>, SheetError> {
+        row.as_ref().ok_or(SheetError {
+            line: self.last_line.max(1),
+            column: 1,
+            kind: SheetErrorKind::MissingRow(label),
+        })
+    }
+}
+
+/// A race sheet: one race per column, and optionally the boat model the
+/// races are run with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RaceSheet {
+    pub races: Vec<Race>,
+    pub boat: Option<Boat>,
+}
+
+/// Reads a sheet with `Time:` and `Distance:` rows in either order, plus an
+/// optional `Boat:` row, treating each column as its own race.
+pub fn parse_race_sheet(text: &str) -> Result<RaceSheet, SheetError> {
+    let rows = read_rows(text)?;
+    let time_row = rows.require(&rows.time, "Time")?;
+    let distance_row = rows.require(&rows.distance, "Distance")?;
+    let times = time_row.numbers()?;
+    let distances = distance_row.numbers()?;
+    if times.len() != distances.len() {
+        let (longer, shorter) = match times.len() > distances.len() {
+            true => (time_row, distance_row),
+            false => (distance_row, time_row),
+        };
+        // Point at the first column that has no partner, or at the end of
+        // the shorter row if the longer one has run out of tokens.
+        let (line, column) = match longer.tokens.get(shorter.tokens.len()) {
+            Some(extra) => (longer.line, extra.column),
+            None => (shorter.line, shorter.end_column),
+        };
+        return Err(SheetError {
+            line,
+            column,
+            kind: SheetErrorKind::ColumnCountMismatch {
+                times: times.len(),
+                distances: distances.len(),
+            },
+        });
+    }
+    let boat = match &rows.boat {
+        Some(row) => {
+            let spec: Vec<&str> = row.tokens.iter().map(|t| t.text).collect();
+            let spec = spec.join(" ");
+            match parse_boat_model(&spec) {
+                Ok(boat) => Some(boat),
+                Err(reason) => {
+                    return Err(SheetError {
+                        line: row.line,
+                        column: row.tokens.first().map_or(row.end_column, |t| t.column),
+                        kind: SheetErrorKind::InvalidBoat(reason),
+                    })
+                }
+            }
+        }
+        None => None,
+    };
+    let races = times
+        .into_iter()
+        .zip(distances)
+        .map(|(allotted_time, record_distance)| Race {
+            allotted_time,
+            record_distance,
+        })
+        .collect();
+    Ok(RaceSheet { races, boat })
+}
+
+/// Reads the sheet as one race, ignoring the spaces between columns.
+pub fn parse_kerned_race<N: RaceInt>(text: &str) -> Result<Race<N>, SheetError> {
+    let rows = read_rows(text)?;
+    Ok(Race {
+        allotted_time: rows.require(&rows.time, "Time")?.kerned()?,
+        record_distance: rows.require(&rows.distance, "Distance")?.kerned()?,
+    })
+}
+
+/// Writes races back out in the sheet format, each column right-aligned
+/// under its widest number.
+pub fn write_race_sheet(races: &[Race]) -> String {
+    let mut time_row = format!("{:<9}", "Time:");
+    let mut distance_row = format!("{:<9}", "Distance:");
+    for race in races {
+        let (time, distance) = (
+            race.allotted_time.to_string(),
+            race.record_distance.to_string(),
+        );
+        let width = time.len().max(distance.len()) + 1;
+        time_row.push_str(&format!(" {:>w$}", time, w = width));
+        distance_row.push_str(&format!(" {:>w$}", distance, w = width));
+    }
+    format!("{}\n{}\n", time_row, distance_row)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boat::Drag;
+    use num_bigint::BigInt;
+
+    const TEST_INPUT: &str = include_str!("../input_test.txt");
+
+    #[test]
+    fn test_parse_test_input() {
+        let sheet = parse_race_sheet(TEST_INPUT).unwrap();
+        assert_eq!(sheet.races.len(), 3);
+        assert_eq!(
+            sheet.races[2],
+            Race {
+                allotted_time: 30,
+                record_distance: 200,
+            }
+        );
+        assert_eq!(sheet.boat, None);
+    }
+
+    #[test]
+    fn test_rows_in_any_order() {
+        let sheet = parse_race_sheet("Boat: drag 10\nDistance: 9 40\n\nTime: 7 15\n").unwrap();
+        assert_eq!(sheet.races[1].allotted_time, 15);
+        assert_eq!(sheet.races[1].record_distance, 40);
+        assert_eq!(sheet.boat, Some(Boat::Drag(Drag::new(10).unwrap())));
+    }
+
+    #[test]
+    fn test_kerned_race() {
+        let race: Race = parse_kerned_race(TEST_INPUT).unwrap();
+        assert_eq!(race.allotted_time, 71530);
+        assert_eq!(race.record_distance, 940200);
+        let text = "Time: 123456789 123456789 123456789\nDistance: 1 2";
+        assert_eq!(
+            parse_kerned_race::<i64>(text),
+            Err(SheetError {
+                line: 1,
+                column: 7,
+                kind: SheetErrorKind::OutOfRange("123456789123456789123456789".to_string()),
+            })
+        );
+        let big: Race<BigInt> = parse_kerned_race(text).unwrap();
+        assert_eq!(big.record_distance, BigInt::from(12));
+    }
+
+    #[test]
+    fn test_invalid_number_position() {
+        assert_eq!(
+            parse_race_sheet("Time:  7  1x5\nDistance: 9 40"),
+            Err(SheetError {
+                line: 1,
+                column: 11,
+                kind: SheetErrorKind::InvalidNumber("1x5".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_column_count_mismatch() {
+        assert_eq!(
+            parse_race_sheet("Time: 7 15 30\nDistance: 9 40"),
+            Err(SheetError {
+                line: 1,
+                column: 12,
+                kind: SheetErrorKind::ColumnCountMismatch {
+                    times: 3,
+                    distances: 2,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_missing_and_duplicate_rows() {
+        assert!(matches!(
+            parse_race_sheet("Time: 7"),
+            Err(SheetError {
+                kind: SheetErrorKind::MissingRow("Distance"),
+                ..
+            })
+        ));
+        assert_eq!(
+            parse_race_sheet("Time: 7\nDistance: 9\n Time: 8"),
+            Err(SheetError {
+                line: 3,
+                column: 2,
+                kind: SheetErrorKind::DuplicateRow("Time".to_string()),
+            })
+        );
+        assert!(matches!(
+            parse_race_sheet("Time: 7\nDistance: 9\nBoat: rowing"),
+            Err(SheetError {
+                line: 3,
+                column: 7,
+                kind: SheetErrorKind::InvalidBoat(_),
+            })
+        ));
+    }
+
+    #[test]
+    fn test_write_race_sheet_round_trip() {
+        let sheet = parse_race_sheet(TEST_INPUT).unwrap();
+        let written = write_race_sheet(&sheet.races);
+        assert_eq!(written.trim_end(), TEST_INPUT.trim_end());
+        assert_eq!(parse_race_sheet(&written).unwrap(), sheet);
+    }
+}
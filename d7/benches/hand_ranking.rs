@@ -0,0 +1,63 @@
+use d7::cards::{Card, CardRules};
+use d7::encoding::HandKey;
+use d7::hands::GameHand;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const HANDS: usize = 1_000_000;
+const CARD_CHARS: [char; 13] = [
+    '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A',
+];
+
+fn random_hands(count: usize) -> Vec<String> {
+    let mut state: u64 = 0x2545F4914F6CDD1D;
+    (0..count)
+        .map(|_| {
+            (0..5)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    CARD_CHARS[(state % 13) as usize]
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn time<T>(run: impl FnOnce() -> T) -> Duration {
+    let start = Instant::now();
+    black_box(run());
+    start.elapsed()
+}
+
+fn main() {
+    let hands = random_hands(HANDS);
+    for rules in [CardRules::Standard, CardRules::JokersWild] {
+        let game_hands = time(|| {
+            let mut parsed: Vec<GameHand> = hands
+                .iter()
+                .map(|h| GameHand::try_from((h.as_str(), rules)).unwrap())
+                .collect();
+            parsed.sort();
+            parsed
+        });
+        let keys = time(|| {
+            let mut keys: Vec<HandKey> = hands
+                .iter()
+                .map(|h| {
+                    let mut cards = [Card::Joker; 5];
+                    for (card, c) in cards.iter_mut().zip(h.chars()) {
+                        *card = Card::parse(&c, rules).unwrap();
+                    }
+                    HandKey::new(&cards)
+                })
+                .collect();
+            keys.sort_unstable();
+            keys
+        });
+        println!("{:?}, {} hands parsed and sorted:", rules, HANDS);
+        println!("  GameHand: {:?}", game_hands);
+        println!("  HandKey:  {:?}", keys);
+    }
+}
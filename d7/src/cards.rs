@@ -1,9 +1,12 @@
 use std::cmp::{Eq, Ord, Ordering, PartialEq, PartialOrd};
 use std::convert::TryFrom;
-use std::usize;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
 pub enum Card {
+    Joker,
     Two,
     Three,
     Four,
@@ -19,8 +22,106 @@ pub enum Card {
     Ace,
 }
 
+/// Which rules to apply when reading cards. Under `JokersWild`, `J` is a
+/// joker that stands in for whatever card makes the best hand, but ranks
+/// below every other card when breaking ties.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum CardRules {
+    #[default]
+    Standard,
+    JokersWild,
+}
+
+/// A character that isn't any card.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct InvalidCard(pub char);
+
+impl Display for InvalidCard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid card {:?}", self.0)
+    }
+}
+
+impl Error for InvalidCard {}
+
+impl Card {
+    /// Every card, lowest first, so that `Card::ALL[card.rank()] == card`.
+    pub const ALL: [Card; 14] = [
+        Self::Joker,
+        Self::Two,
+        Self::Three,
+        Self::Four,
+        Self::Five,
+        Self::Six,
+        Self::Seven,
+        Self::Eight,
+        Self::Nine,
+        Self::Ten,
+        Self::Jack,
+        Self::Queen,
+        Self::King,
+        Self::Ace,
+    ];
+
+    pub fn rank(&self) -> usize {
+        *self as usize
+    }
+
+    pub fn from_rank(rank: usize) -> Option<Self> {
+        Self::ALL.get(rank).copied()
+    }
+
+    pub fn parse(value: &char, rules: CardRules) -> Result<Self, InvalidCard> {
+        match (value, rules) {
+            ('J', CardRules::JokersWild) => Ok(Self::Joker),
+            _ => Self::try_from(value),
+        }
+    }
+}
+
+impl Card {
+    /// The character a card is written as. Jokers are written `J`, like the
+    /// jacks they replace.
+    pub fn to_char(&self) -> char {
+        match self {
+            Self::Joker | Self::Jack => 'J',
+            Self::Ace => 'A',
+            Self::King => 'K',
+            Self::Queen => 'Q',
+            Self::Ten => 'T',
+            Self::Nine => '9',
+            Self::Eight => '8',
+            Self::Seven => '7',
+            Self::Six => '6',
+            Self::Five => '5',
+            Self::Four => '4',
+            Self::Three => '3',
+            Self::Two => '2',
+        }
+    }
+}
+
+impl Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+impl FromStr for Card {
+    type Err = InvalidCard;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(card), None) => Self::try_from(&card),
+            (Some(card), Some(_)) => Err(InvalidCard(card)),
+            (None, _) => Err(InvalidCard(' ')),
+        }
+    }
+}
+
 impl TryFrom<&char> for Card {
-    type Error = &'static str;
+    type Error = InvalidCard;
 
     fn try_from(value: &char) -> Result<Self, Self::Error> {
         match value {
@@ -37,44 +138,179 @@ impl TryFrom<&char> for Card {
             '4' => Ok(Self::Four),
             '3' => Ok(Self::Three),
             '2' => Ok(Self::Two),
-            _ => Err("Invalid card"),
+            _ => Err(InvalidCard(*value)),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+/// A character that isn't any suit.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct InvalidSuit(pub char);
+
+impl Display for InvalidSuit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid suit {:?}", self.0)
+    }
+}
+
+impl Error for InvalidSuit {}
+
+impl Suit {
+    pub fn to_char(&self) -> char {
+        match self {
+            Self::Clubs => 'c',
+            Self::Diamonds => 'd',
+            Self::Hearts => 'h',
+            Self::Spades => 's',
         }
     }
 }
 
+impl Display for Suit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+impl TryFrom<&char> for Suit {
+    type Error = InvalidSuit;
+
+    fn try_from(value: &char) -> Result<Self, Self::Error> {
+        match value {
+            'c' => Ok(Self::Clubs),
+            'd' => Ok(Self::Diamonds),
+            'h' => Ok(Self::Hearts),
+            's' => Ok(Self::Spades),
+            _ => Err(InvalidSuit(*value)),
+        }
+    }
+}
+
+/// A card from a standard deck, written rank then suit, like `As` or `Td`.
+/// There are no jokers in a standard deck.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub struct SuitedCard {
+    pub card: Card,
+    pub suit: Suit,
+}
+
+/// Text that can't be read as a suited card.
 #[derive(PartialEq, Eq, Clone, Debug)]
+pub enum SuitedCardError {
+    InvalidCard(InvalidCard),
+    InvalidSuit(InvalidSuit),
+    /// Anything but exactly a rank followed by a suit.
+    WrongLength(String),
+}
+
+impl Display for SuitedCardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidCard(err) => write!(f, "{}", err),
+            Self::InvalidSuit(err) => write!(f, "{}", err),
+            Self::WrongLength(value) => {
+                write!(f, "{:?} isn't a rank followed by a suit", value)
+            }
+        }
+    }
+}
+
+impl Error for SuitedCardError {}
+
+impl From<InvalidCard> for SuitedCardError {
+    fn from(err: InvalidCard) -> Self {
+        Self::InvalidCard(err)
+    }
+}
+
+impl From<InvalidSuit> for SuitedCardError {
+    fn from(err: InvalidSuit) -> Self {
+        Self::InvalidSuit(err)
+    }
+}
+
+impl Display for SuitedCard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.card, self.suit)
+    }
+}
+
+impl TryFrom<&str> for SuitedCard {
+    type Error = SuitedCardError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let chars: Vec<_> = value.chars().collect();
+        match chars[..] {
+            [card, suit] => Ok(Self {
+                card: Card::try_from(&card)?,
+                suit: Suit::try_from(&suit)?,
+            }),
+            _ => Err(SuitedCardError::WrongLength(value.to_string())),
+        }
+    }
+}
+
+/// Reads whitespace-separated suited cards, like `As Kd 7h`.
+pub fn parse_suited_cards(value: &str) -> Result<Vec<SuitedCard>, SuitedCardError> {
+    value.split_whitespace().map(SuitedCard::try_from).collect()
+}
+
+/// Kicker cards. Their order doesn't matter: two sets of spares are equal
+/// when they hold the same cards, and otherwise compare highest card first.
+#[derive(Clone, Debug)]
 pub struct SpareCards<const N: usize> {
     pub cards: [Card; N],
 }
 
-impl<const N: usize> PartialOrd for SpareCards<N> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+impl<const N: usize> Ord for SpareCards<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
         let mut self_cards: Vec<_> = self.cards.iter().collect();
         let mut other_cards: Vec<_> = other.cards.iter().collect();
         self_cards.sort_by(|a, b| b.cmp(a));
         other_cards.sort_by(|a, b| b.cmp(a));
-        let mut self_iter = self_cards.iter();
-        let mut other_iter = other_cards.iter();
-        loop {
-            let next_self = self_iter.next();
-            let next_other = other_iter.next();
-            match (next_self, next_other) {
-                (Some(self_high), Some(other_high)) => match self_high.cmp(other_high) {
-                    Ordering::Equal => continue,
-                    neq => return Some(neq),
-                },
-                (Some(_), None) | (None, Some(_)) => panic!("Unequal N for SpareCards<N>"),
-                _ => break,
-            }
-        }
-        Some(Ordering::Equal)
+        self_cards.cmp(&other_cards)
     }
 }
 
+/// Highest card first, separated by commas.
+impl<const N: usize> Display for SpareCards<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut cards = self.cards;
+        cards.sort_by(|a, b| b.cmp(a));
+        let cards: Vec<_> = cards.iter().map(|card| card.to_string()).collect();
+        write!(f, "{}", cards.join(","))
+    }
+}
+
+impl<const N: usize> PartialOrd for SpareCards<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize> PartialEq for SpareCards<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<const N: usize> Eq for SpareCards<N> {}
+
 #[cfg(test)]
 mod tests {
     use super::Card::*;
-    use super::SpareCards;
+    use super::{
+        parse_suited_cards, Card, CardRules, InvalidCard, InvalidSuit, SpareCards, Suit,
+        SuitedCard, SuitedCardError,
+    };
     use std::cmp::Ordering;
 
     #[test]
@@ -84,6 +320,58 @@ mod tests {
         assert!(Queen < King);
     }
 
+    #[test]
+    fn test_card_rank_round_trip() {
+        for card in Card::ALL {
+            assert!(Card::from_rank(card.rank()) == Some(card));
+        }
+        assert!(Card::from_rank(14).is_none());
+    }
+
+    #[test]
+    fn test_joker_ord() {
+        assert!(Joker < Two);
+        assert!(Card::parse(&'J', CardRules::JokersWild) == Ok(Joker));
+        assert!(Card::parse(&'J', CardRules::Standard) == Ok(Jack));
+        assert!(Card::parse(&'1', CardRules::JokersWild) == Err(InvalidCard('1')));
+    }
+
+    #[test]
+    fn test_card_text_round_trip() {
+        for card in Card::ALL {
+            let text = card.to_string();
+            let rules = match card {
+                Joker => CardRules::JokersWild,
+                _ => CardRules::Standard,
+            };
+            let char = text.chars().next().unwrap();
+            assert!(Card::parse(&char, rules) == Ok(card));
+        }
+        assert!("Q".parse::<Card>() == Ok(Queen));
+        assert!("QQ".parse::<Card>().is_err());
+        assert!("".parse::<Card>().is_err());
+        let spares = SpareCards {
+            cards: [Seven, Ace, Two],
+        };
+        assert!(spares.to_string() == "A,7,2");
+    }
+
+    #[test]
+    fn test_parse_suited_card() {
+        let card = SuitedCard::try_from("Td").unwrap();
+        assert!(card.card == Ten && card.suit == Suit::Diamonds);
+        assert!(card.to_string() == "Td");
+        assert!(SuitedCard::try_from("1s") == Err(InvalidCard('1').into()));
+        assert!(SuitedCard::try_from("Ax") == Err(InvalidSuit('x').into()));
+        assert!(
+            SuitedCard::try_from("Asd") == Err(SuitedCardError::WrongLength("Asd".to_string()))
+        );
+        let cards = parse_suited_cards("As  Kd 7h").unwrap();
+        assert!(cards.len() == 3 && cards[2].card == Seven);
+        let err = parse_suited_cards("As Kx").unwrap_err();
+        assert!(err.to_string() == "invalid suit 'x'");
+    }
+
     #[test]
     fn test_spare_cards_eq() {
         let some_cards = SpareCards {
@@ -93,6 +381,7 @@ mod tests {
             cards: [Queen, Seven, Jack],
         };
         assert!(some_cards.partial_cmp(&some_other_cards) == Some(Ordering::Equal));
+        assert!(some_cards == some_other_cards);
     }
 
     #[test]
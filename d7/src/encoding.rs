@@ -0,0 +1,126 @@
+use crate::cards::Card;
+use crate::hands::{GameHand, GenericHand, HandCategory};
+
+const RANK_BITS: u32 = 4;
+const KIND_SHIFT: u32 = RANK_BITS * 5;
+
+/// A hand packed into one integer that sorts exactly like `GameHand`: the
+/// kind in the top bits, then the rank of each card in the order dealt.
+/// Building one needs no allocation, just a count per rank.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub struct HandKey(u32);
+
+/// The kind of hand a count of ranks makes, from 0 (high card) to 6 (five
+/// of a kind), with jokers joining whichever card is most common.
+fn kind_order(cards: &[Card; 5]) -> u32 {
+    let mut counts = [0_u8; Card::ALL.len()];
+    for card in cards {
+        counts[card.rank()] += 1;
+    }
+    let jokers = counts[Card::Joker.rank()];
+    let (mut most, mut second) = (0, 0);
+    for count in &counts[Card::Two.rank()..] {
+        if *count > most {
+            second = most;
+            most = *count;
+        } else if *count > second {
+            second = *count;
+        }
+    }
+    match (most + jokers, second) {
+        (5, _) => 6,
+        (4, _) => 5,
+        (3, 2) => 4,
+        (3, _) => 3,
+        (2, 2) => 2,
+        (2, _) => 1,
+        _ => 0,
+    }
+}
+
+impl HandKey {
+    pub fn new(cards: &[Card; 5]) -> Self {
+        let ranks = cards
+            .iter()
+            .fold(0, |acc, card| (acc << RANK_BITS) | card.rank() as u32);
+        Self((kind_order(cards) << KIND_SHIFT) | ranks)
+    }
+
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+
+    /// The same number `GameHand` ranks its kind by.
+    pub fn kind_order(&self) -> usize {
+        (self.0 >> KIND_SHIFT) as usize
+    }
+
+    pub fn category(&self) -> HandCategory {
+        HandCategory::ALL[self.kind_order()]
+    }
+
+    pub fn cards(&self) -> [Card; 5] {
+        let mask = (1 << RANK_BITS) - 1;
+        let mut cards = [Card::Joker; 5];
+        for (idx, card) in cards.iter_mut().enumerate() {
+            let shift = RANK_BITS * (4 - idx as u32);
+            *card = Card::from_rank(((self.0 >> shift) & mask) as usize).unwrap();
+        }
+        cards
+    }
+
+    pub fn decode(&self) -> GameHand {
+        GameHand::try_from(&GenericHand::new(&self.cards())).unwrap()
+    }
+}
+
+impl From<&GameHand> for HandKey {
+    fn from(hand: &GameHand) -> Self {
+        Self::new(&hand.cards)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::CardRules;
+
+    const TEST_INPUT: &str = include_str!("../input_test.txt");
+
+    fn test_hands(rules: CardRules) -> Vec<GameHand> {
+        TEST_INPUT
+            .lines()
+            .map(|line| GameHand::try_from((&line[..5], rules)).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_key_order_matches_hand_order() {
+        for rules in [CardRules::Standard, CardRules::JokersWild] {
+            let hands = test_hands(rules);
+            for a in &hands {
+                for b in &hands {
+                    assert_eq!(HandKey::from(a).cmp(&HandKey::from(b)), a.cmp(b));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_round_trip() {
+        for rules in [CardRules::Standard, CardRules::JokersWild] {
+            for hand in test_hands(rules) {
+                assert_eq!(HandKey::from(&hand).decode(), hand);
+            }
+        }
+    }
+
+    #[test]
+    fn test_kind_order() {
+        let key = HandKey::from(&GameHand::try_from(("KTJJT", CardRules::JokersWild)).unwrap());
+        assert_eq!(key.kind_order(), 5);
+        assert_eq!(key.category(), HandCategory::FourOfAKind);
+        let key = HandKey::from(&GameHand::try_from("KTJJT").unwrap());
+        assert_eq!(key.kind_order(), 2);
+    }
+}
@@ -0,0 +1,560 @@
+use crate::cards::{Card, CardRules};
+use crate::hands::{GameHand, GenericHand, HandCategory};
+use std::cmp::Ordering;
+use std::error::Error;
+use std::{fmt, iter};
+
+/// Why odds can't be worked out for the given cards and deck.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum EquityError {
+    /// A held card that isn't in what's left of the deck.
+    CardNotInDeck(Card),
+    NotEnoughCards {
+        needed: usize,
+        left: usize,
+    },
+    /// A partial hand holding more than five cards.
+    TooManyCards(usize),
+    /// Sampling was called for with no samples to draw.
+    NoSamples,
+}
+
+impl fmt::Display for EquityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::CardNotInDeck(card) => write!(f, "{} isn't left in the deck", card),
+            Self::NotEnoughCards { needed, left } => {
+                write!(f, "need {} cards but the deck has {} left", needed, left)
+            }
+            Self::TooManyCards(found) => {
+                write!(f, "a hand has at most five cards, found {}", found)
+            }
+            Self::NoSamples => write!(f, "need at least one sample"),
+        }
+    }
+}
+
+impl Error for EquityError {}
+
+/// The cards still to be dealt. Copies of the same card are separate cards,
+/// so every card in the deck is equally likely to be drawn.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Deck {
+    cards: Vec<Card>,
+}
+
+impl Deck {
+    pub fn new(cards: Vec<Card>) -> Self {
+        Self { cards }
+    }
+
+    /// `copies` of every rank from two to ace. Under `JokersWild` the jacks
+    /// are jokers.
+    pub fn with_copies(copies: usize, rules: CardRules) -> Self {
+        let cards = Card::ALL[Card::Two.rank()..]
+            .iter()
+            .map(|card| match (card, rules) {
+                (Card::Jack, CardRules::JokersWild) => Card::Joker,
+                _ => *card,
+            })
+            .flat_map(|card| iter::repeat_n(card, copies))
+            .collect();
+        Self { cards }
+    }
+
+    /// Four of every rank, like a deck of playing cards without suits.
+    pub fn standard(rules: CardRules) -> Self {
+        Self::with_copies(4, rules)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// The deck once `dealt` cards have been taken out of it.
+    pub fn without(&self, dealt: &[Card]) -> Result<Self, EquityError> {
+        let mut cards = self.cards.clone();
+        for card in dealt {
+            match cards.iter().position(|c| c == card) {
+                Some(idx) => cards.swap_remove(idx),
+                None => return Err(EquityError::CardNotInDeck(*card)),
+            };
+        }
+        Ok(Self { cards })
+    }
+}
+
+/// How to explore the ways the rest of a hand could be dealt. Enumeration
+/// is used whenever there are at most `exhaustive_limit` ways to deal the
+/// missing cards, counting each hand's cards in any order as one deal;
+/// otherwise `samples` deals are drawn at random, seeded so that the same
+/// options always give the same answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EquityOptions {
+    pub exhaustive_limit: u64,
+    pub samples: u64,
+    pub seed: u64,
+}
+
+impl Default for EquityOptions {
+    fn default() -> Self {
+        Self {
+            exhaustive_limit: 1_000_000,
+            samples: 100_000,
+            seed: 7,
+        }
+    }
+}
+
+fn rate(count: u64, total: u64) -> f64 {
+    match total {
+        0 => 0.0,
+        _ => count as f64 / total as f64,
+    }
+}
+
+/// How often a partial hand ends up as each kind, counted over every deal
+/// that was looked at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KindOdds {
+    /// Indexed like `HandCategory::ALL`.
+    counts: [u64; 7],
+    pub total: u64,
+    pub exhaustive: bool,
+}
+
+impl KindOdds {
+    /// How many of the deals made `category`.
+    pub fn count(&self, category: HandCategory) -> u64 {
+        self.counts[category as usize]
+    }
+
+    /// The chance of `category`, or zero if no deals were looked at.
+    pub fn probability(&self, category: HandCategory) -> f64 {
+        rate(self.count(category), self.total)
+    }
+}
+
+impl fmt::Display for KindOdds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let method = if self.exhaustive {
+            "every deal"
+        } else {
+            "sampled deals"
+        };
+        writeln!(f, "Over {} {}:", self.total, method)?;
+        for category in HandCategory::ALL.into_iter().rev() {
+            let name = category.to_string();
+            let percent = self.probability(category) * 100.0;
+            writeln!(f, "  {:<16}{:>8.4}%", name, percent)?;
+        }
+        Ok(())
+    }
+}
+
+/// How two partial hands fare against each other once both are dealt out.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeadToHead {
+    pub wins: u64,
+    pub ties: u64,
+    pub losses: u64,
+    pub exhaustive: bool,
+}
+
+impl HeadToHead {
+    pub fn total(&self) -> u64 {
+        self.wins + self.ties + self.losses
+    }
+
+    /// Zero if no deals were looked at, like the other rates.
+    pub fn win_rate(&self) -> f64 {
+        rate(self.wins, self.total())
+    }
+
+    pub fn tie_rate(&self) -> f64 {
+        rate(self.ties, self.total())
+    }
+
+    pub fn loss_rate(&self) -> f64 {
+        rate(self.losses, self.total())
+    }
+}
+
+impl fmt::Display for HeadToHead {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "win {:.4}%, tie {:.4}%, lose {:.4}% over {} deals",
+            self.win_rate() * 100.0,
+            self.tie_rate() * 100.0,
+            self.loss_rate() * 100.0,
+            self.total()
+        )
+    }
+}
+
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// A value below `bound`, which must not be zero.
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+/// The number of ways to pick `k` of `n` cards, or `None` if that doesn't
+/// fit in a `u64`.
+fn choose(n: usize, k: usize) -> Option<u64> {
+    if k > n {
+        return Some(0);
+    }
+    // Each partial product is itself a binomial coefficient, so the
+    // division is always exact.
+    (0..k.min(n - k)).try_fold(1_u64, |acc, i| {
+        let next = (acc as u128).checked_mul((n - i) as u128)? / (i as u128 + 1);
+        u64::try_from(next).ok()
+    })
+}
+
+/// The number of ways to deal a hand of each size in `groups` from
+/// `deck_size` cards, with the order of cards within a hand ignored, or
+/// `None` if that doesn't fit in a `u64`.
+fn deals(deck_size: usize, groups: &[usize]) -> Option<u64> {
+    let mut left = deck_size;
+    groups.iter().try_fold(1_u64, |acc, group| {
+        let ways = choose(left, *group)?;
+        left -= group;
+        acc.checked_mul(ways)
+    })
+}
+
+/// Calls `visit` with each deal of `groups` cards from `deck`, one group
+/// after another, and reports whether every deal was visited rather than a
+/// sample.
+fn for_each_deal(
+    deck: &Deck,
+    groups: &[usize],
+    options: &EquityOptions,
+    mut visit: impl FnMut(&[Card]),
+) -> Result<bool, EquityError> {
+    let draws = groups.iter().sum();
+    if draws > deck.len() {
+        return Err(EquityError::NotEnoughCards {
+            needed: draws,
+            left: deck.len(),
+        });
+    }
+    let exhaustive =
+        deals(deck.len(), groups).is_some_and(|deals| deals <= options.exhaustive_limit);
+    if exhaustive {
+        let mut used = vec![false; deck.len()];
+        let mut dealt = Vec::with_capacity(draws);
+        deal_every_way(&deck.cards, 0, groups, 0, &mut used, &mut dealt, &mut visit);
+    } else {
+        if options.samples == 0 {
+            return Err(EquityError::NoSamples);
+        }
+        let mut rng = SplitMix64::new(options.seed);
+        let mut cards = deck.cards.clone();
+        for _ in 0..options.samples {
+            // A partial Fisher-Yates shuffle: the first `draws` cards are the deal.
+            for i in 0..draws {
+                let j = i + rng.below(cards.len() - i);
+                cards.swap(i, j);
+            }
+            visit(&cards[..draws]);
+        }
+    }
+    Ok(exhaustive)
+}
+
+/// Deals the `left_in_group` cards still missing from the current group and
+/// then each of `later_groups`. A group's cards are picked in deck order,
+/// from `from` onwards, so each group is dealt once whatever its order.
+fn deal_every_way(
+    deck: &[Card],
+    left_in_group: usize,
+    later_groups: &[usize],
+    from: usize,
+    used: &mut [bool],
+    dealt: &mut Vec<Card>,
+    visit: &mut impl FnMut(&[Card]),
+) {
+    if left_in_group == 0 {
+        match later_groups.split_first() {
+            Some((next, rest)) => deal_every_way(deck, *next, rest, 0, used, dealt, visit),
+            None => visit(dealt),
+        }
+        return;
+    }
+    for idx in from..deck.len() {
+        if used[idx] {
+            continue;
+        }
+        used[idx] = true;
+        dealt.push(deck[idx]);
+        deal_every_way(
+            deck,
+            left_in_group - 1,
+            later_groups,
+            idx + 1,
+            used,
+            dealt,
+            visit,
+        );
+        dealt.pop();
+        used[idx] = false;
+    }
+}
+
+/// The hand made by dealing `drawn` after the cards already held.
+fn complete(partial: &[Card], drawn: &[Card]) -> GameHand {
+    let mut cards = [Card::Joker; 5];
+    for (card, dealt) in cards.iter_mut().zip(partial.iter().chain(drawn)) {
+        *card = *dealt;
+    }
+    GameHand::try_from(&GenericHand::new(&cards)).unwrap()
+}
+
+fn check_partial(partial: &[Card]) -> Result<(), EquityError> {
+    if partial.len() > 5 {
+        return Err(EquityError::TooManyCards(partial.len()));
+    }
+    Ok(())
+}
+
+/// The chance of `partial` becoming each kind once the rest of its five
+/// cards are dealt from `deck`, which should still hold the cards in
+/// `partial`.
+pub fn kind_odds(
+    partial: &[Card],
+    deck: &Deck,
+    options: &EquityOptions,
+) -> Result<KindOdds, EquityError> {
+    check_partial(partial)?;
+    let deck = deck.without(partial)?;
+    let mut counts = [0; 7];
+    let exhaustive = for_each_deal(&deck, &[5 - partial.len()], options, |drawn| {
+        counts[complete(partial, drawn).category() as usize] += 1;
+    })?;
+    Ok(KindOdds {
+        counts,
+        total: counts.iter().sum(),
+        exhaustive,
+    })
+}
+
+/// How often `first` beats, ties or loses to `second` when both are dealt
+/// out from the same `deck`, `first` drawing before `second`.
+pub fn head_to_head(
+    first: &[Card],
+    second: &[Card],
+    deck: &Deck,
+    options: &EquityOptions,
+) -> Result<HeadToHead, EquityError> {
+    check_partial(first)?;
+    check_partial(second)?;
+    let deck = deck.without(first)?.without(second)?;
+    let (first_draws, second_draws) = (5 - first.len(), 5 - second.len());
+    let mut result = HeadToHead {
+        wins: 0,
+        ties: 0,
+        losses: 0,
+        exhaustive: false,
+    };
+    let groups = [first_draws, second_draws];
+    result.exhaustive = for_each_deal(&deck, &groups, options, |drawn| {
+        let a = complete(first, &drawn[..first_draws]);
+        let b = complete(second, &drawn[first_draws..]);
+        match a.cmp(&b) {
+            Ordering::Greater => result.wins += 1,
+            Ordering::Equal => result.ties += 1,
+            Ordering::Less => result.losses += 1,
+        }
+    })?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::Card::*;
+
+    #[test]
+    fn test_deck() {
+        let deck = Deck::standard(CardRules::Standard);
+        assert_eq!(deck.len(), 52);
+        assert!(!Deck::standard(CardRules::JokersWild).cards.contains(&Jack));
+        let deck = Deck::with_copies(1, CardRules::Standard);
+        assert_eq!(
+            deck.without(&[Ace, Ace]).unwrap_err(),
+            EquityError::CardNotInDeck(Ace)
+        );
+        assert_eq!(deck.without(&[Ace]).unwrap().len(), 12);
+    }
+
+    #[test]
+    fn test_impossible_deals() {
+        let options = EquityOptions::default();
+        let six = [King, King, Six, Six, Seven, Seven];
+        let deck = Deck::standard(CardRules::Standard);
+        assert_eq!(
+            kind_odds(&six, &deck, &options).unwrap_err(),
+            EquityError::TooManyCards(6)
+        );
+        let deck = Deck::new(vec![King, Queen, Two]);
+        assert_eq!(
+            kind_odds(&[King], &deck, &options).unwrap_err(),
+            EquityError::NotEnoughCards { needed: 4, left: 2 }
+        );
+    }
+
+    #[test]
+    fn test_complete_hand_is_certain() {
+        let deck = Deck::standard(CardRules::Standard);
+        let odds = kind_odds(&[King, King, Six, Seven, Seven], &deck, &Default::default()).unwrap();
+        assert!(odds.exhaustive);
+        assert_eq!(odds.total, 1);
+        assert_eq!(odds.probability(HandCategory::TwoPair), 1.0);
+    }
+
+    #[test]
+    fn test_exhaustive_odds() {
+        // Three of a kind with one card to come: 48 cards left, 1 of them
+        // the last king, and 3 pairing the six for a full house.
+        let deck = Deck::standard(CardRules::Standard);
+        let odds = kind_odds(&[King, King, King, Six], &deck, &Default::default());
+        let odds = odds.unwrap();
+        assert!(odds.exhaustive);
+        assert_eq!(odds.total, 48);
+        assert_eq!(odds.count(HandCategory::FourOfAKind), 1);
+        assert_eq!(odds.count(HandCategory::FullHouse), 3);
+        assert_eq!(odds.count(HandCategory::ThreeOfAKind), 44);
+        let shown = odds.to_string();
+        assert!(shown.starts_with("Over 48 every deal:\n  Five of a Kind"));
+        assert!(shown.contains("  Four of a Kind    2.0833%\n"));
+        assert!(shown.ends_with("  High Card         0.0000%\n"));
+    }
+
+    #[test]
+    fn test_exhaustive_deals_ignore_order() {
+        // Four cards to come from the 51 left: C(51, 4) deals, not the
+        // 6.2 million ways to deal them in order.
+        let deck = Deck::standard(CardRules::Standard);
+        let odds = kind_odds(&[King], &deck, &Default::default()).unwrap();
+        assert!(odds.exhaustive);
+        assert_eq!(odds.total, 249_900);
+        assert_eq!(deals(51, &[4]), Some(249_900));
+        assert_eq!(deals(44, &[1, 1]), Some(44 * 43));
+        assert_eq!(deals(10, &[2, 3]), Some(45 * 56));
+        assert_eq!(deals(52, &[0]), Some(1));
+        assert_eq!(deals(200, &[100]), None);
+        assert_eq!(choose(3, 4), Some(0));
+    }
+
+    #[test]
+    fn test_no_samples() {
+        let deck = Deck::standard(CardRules::Standard);
+        let options = EquityOptions {
+            exhaustive_limit: 0,
+            samples: 0,
+            seed: 1,
+        };
+        assert_eq!(
+            kind_odds(&[], &deck, &options).unwrap_err(),
+            EquityError::NoSamples
+        );
+        assert_eq!(
+            head_to_head(&[], &[], &deck, &options).unwrap_err(),
+            EquityError::NoSamples
+        );
+        let empty = HeadToHead {
+            wins: 0,
+            ties: 0,
+            losses: 0,
+            exhaustive: true,
+        };
+        assert_eq!(empty.win_rate(), 0.0);
+        let empty = KindOdds {
+            counts: [0; 7],
+            total: 0,
+            exhaustive: true,
+        };
+        assert_eq!(empty.probability(HandCategory::HighCard), 0.0);
+    }
+
+    #[test]
+    fn test_sampled_odds_are_reproducible() {
+        let deck = Deck::standard(CardRules::Standard);
+        let options = EquityOptions {
+            exhaustive_limit: 0,
+            samples: 20_000,
+            seed: 42,
+        };
+        let odds = kind_odds(&[], &deck, &options).unwrap();
+        assert!(!odds.exhaustive);
+        assert_eq!(odds.total, 20_000);
+        assert_eq!(odds, kind_odds(&[], &deck, &options).unwrap());
+        // A pair turns up in about 42% of five card deals.
+        assert!((odds.probability(HandCategory::Pair) - 0.4226).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_head_to_head() {
+        let deck = Deck::standard(CardRules::Standard);
+        let options = EquityOptions::default();
+        let result = head_to_head(
+            &[Ace, Ace, Ace, Ace, Two],
+            &[King, King, Queen, Queen],
+            &deck,
+            &options,
+        );
+        let result = result.unwrap();
+        assert!(result.exhaustive);
+        assert_eq!((result.wins, result.ties, result.losses), (43, 0, 0));
+        let result = head_to_head(
+            &[Two, Three, Four, Five],
+            &[Two, Three, Four, Five],
+            &deck,
+            &options,
+        );
+        let result = result.unwrap();
+        assert_eq!(result.wins, result.losses);
+        assert!(result.ties > 0);
+    }
+
+    #[test]
+    fn test_jokers_change_odds() {
+        let options = EquityOptions::default();
+        let standard = kind_odds(
+            &[Two, Three, Four],
+            &Deck::standard(CardRules::Standard),
+            &options,
+        );
+        let wild = kind_odds(
+            &[Two, Three, Four],
+            &Deck::standard(CardRules::JokersWild),
+            &options,
+        );
+        let high_card = HandCategory::HighCard;
+        assert!(wild.unwrap().probability(high_card) < standard.unwrap().probability(high_card));
+    }
+}
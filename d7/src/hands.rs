@@ -1,17 +1,65 @@
-use crate::cards::{Card, SpareCards};
+use crate::cards::{Card, CardRules, SpareCards};
+use crate::encoding::HandKey;
 use std::{
-    cmp::{max, min, Ordering, PartialEq, PartialOrd},
+    cmp::{max, min, Ord, Ordering, PartialEq, PartialOrd},
     collections::HashMap,
+    error::Error,
+    fmt::{self, Display},
+    str::FromStr,
 };
 
+/// Why some text isn't a hand. Positions count from 1.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum HandError {
+    InvalidCard { position: usize, card: char },
+    WrongLength { expected: usize, found: usize },
+}
+
+impl Display for HandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HandError::InvalidCard { position, card } => {
+                write!(f, "invalid card {:?} at position {}", card, position)
+            }
+            HandError::WrongLength { expected, found } => {
+                write!(f, "expected {} cards but found {}", expected, found)
+            }
+        }
+    }
+}
+
+impl Error for HandError {}
+
+/// A hand that isn't the kind it was checked against, such as a pair
+/// checked for a full house.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct NotThisKind;
+
+impl Display for NotThisKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "hand isn't this kind")
+    }
+}
+
+impl Error for NotThisKind {}
+
+/// Any `N` cards, counted up by rank. Five cards make a `GameHand`; see
+/// `SizedHand` for other sizes.
 #[derive(Clone, Debug)]
-pub struct GenericHand {
-    pub cards: [Card; 5],
+pub struct GenericHand<const N: usize = 5> {
+    pub cards: [Card; N],
     counts: HashMap<Card, usize>,
 }
 
-impl GenericHand {
-    pub fn new(cards: &[Card; 5]) -> Self {
+impl<const N: usize> GenericHand<N> {
+    /// Reads a hand under `rules`, like `Card::parse`. Jokers are written
+    /// `J`, so a hand holding them only reads back the same under
+    /// `CardRules::JokersWild`.
+    pub fn parse(value: &str, rules: CardRules) -> Result<Self, HandError> {
+        Self::try_from((value, rules))
+    }
+
+    pub fn new(cards: &[Card; N]) -> Self {
         let mut counts: HashMap<Card, usize> = HashMap::new();
         for card in cards {
             counts
@@ -19,100 +67,190 @@ impl GenericHand {
                 .and_modify(|a| *a += 1)
                 .or_insert(1);
         }
+        Self::count_jokers_as_best_card(&mut counts);
         Self {
             cards: cards.to_owned(),
-            counts: counts,
+            counts,
+        }
+    }
+
+    /// Jokers join whichever card already appears most often (the higher card
+    /// on a tie), which always yields the strongest available kind. A hand of
+    /// nothing but jokers is left alone.
+    fn count_jokers_as_best_card(counts: &mut HashMap<Card, usize>) {
+        let best = counts
+            .iter()
+            .filter(|(card, _)| **card != Card::Joker)
+            .max_by_key(|(card, count)| (**count, **card))
+            .map(|(card, _)| card.to_owned());
+        if let Some(best) = best {
+            if let Some(jokers) = counts.remove(&Card::Joker) {
+                counts.entry(best).and_modify(|a| *a += jokers);
+            }
         }
     }
 
     pub fn find_all_n_of(&self, n: usize) -> Vec<&Card> {
         self.counts
             .iter()
-            .filter_map(|entry| match entry {
-                (card, count) => {
-                    if *count == n {
-                        Some(card)
-                    } else {
-                        None
-                    }
-                }
-            })
+            .filter_map(|(card, count)| if *count == n { Some(card) } else { None })
             .collect()
     }
 
     pub fn find_n_of(&self, n: usize) -> Option<&Card> {
         self.find_all_n_of(n).first().copied()
     }
+
+    /// How many of each card the hand holds, largest count first and padded
+    /// with zeros, so `[3, 2, 0, 0, 0]` for a full house.
+    pub fn count_pattern(&self) -> [usize; N] {
+        let mut pattern = [0; N];
+        let mut counts: Vec<_> = self.counts.values().copied().collect();
+        counts.sort_by(|a, b| b.cmp(a));
+        pattern[..counts.len()].copy_from_slice(&counts);
+        pattern
+    }
+
+    /// The cards that make up the hand's groups, largest group first and
+    /// higher cards first within groups of the same size.
+    pub fn defining_cards(&self) -> Vec<Card> {
+        let mut groups: Vec<_> = self.counts.iter().collect();
+        groups.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(b.cmp(a)));
+        groups
+            .into_iter()
+            .map(|(card, _)| card.to_owned())
+            .collect()
+    }
 }
 
-impl TryFrom<&str> for GenericHand {
-    type Error = ();
+impl<const N: usize> TryFrom<(&str, CardRules)> for GenericHand<N> {
+    type Error = HandError;
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let chars: Vec<_> = value.chars().collect();
-        if chars.len() != 5 {
-            return Err(());
-        }
-        let mut card_vec = Vec::new();
-        for char in chars {
-            let card = Card::try_from(&char);
-            if card.is_err() {
-                return Err(());
-            }
-            card_vec.push(card.unwrap());
+    fn try_from((value, rules): (&str, CardRules)) -> Result<Self, Self::Error> {
+        let found = value.chars().count();
+        if found != N {
+            return Err(HandError::WrongLength { expected: N, found });
+        }
+        let mut cards = [Card::Joker; N];
+        for (idx, (card, char)) in cards.iter_mut().zip(value.chars()).enumerate() {
+            *card = Card::parse(&char, rules).map_err(|_| HandError::InvalidCard {
+                position: idx + 1,
+                card: char,
+            })?;
         }
-        Ok(Self::new(&[
-            card_vec[0],
-            card_vec[1],
-            card_vec[2],
-            card_vec[3],
-            card_vec[4],
-        ]))
+        Ok(Self::new(&cards))
     }
 }
 
-#[derive(PartialEq, Clone, Debug)]
+impl<const N: usize> Display for GenericHand<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for card in &self.cards {
+            write!(f, "{}", card)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads a hand under `CardRules::Standard`, where `J` is always a jack.
+/// Use `GenericHand::parse` to read jokers back.
+impl<const N: usize> FromStr for GenericHand<N> {
+    type Err = HandError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+impl<const N: usize> TryFrom<&str> for GenericHand<N> {
+    type Error = HandError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::try_from((value, CardRules::Standard))
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct FiveOfAKind {
     kind: Card,
 }
 
+impl Ord for FiveOfAKind {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.kind.cmp(&other.kind)
+    }
+}
+
 impl PartialOrd for FiveOfAKind {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.kind.partial_cmp(&other.kind)
+        Some(self.cmp(other))
+    }
+}
+
+impl FiveOfAKind {
+    /// The card all five are.
+    pub fn kind(&self) -> Card {
+        self.kind
+    }
+}
+
+impl Display for FiveOfAKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Five of a Kind: {}", self.kind)
     }
 }
 
 impl TryFrom<&GenericHand> for FiveOfAKind {
-    type Error = ();
+    type Error = NotThisKind;
 
     fn try_from(hand: &GenericHand) -> Result<Self, Self::Error> {
-        if hand.counts.len() == 1 {
-            Ok(Self {
-                kind: hand.cards[0].to_owned(),
-            })
-        } else {
-            Err(())
+        match hand.find_n_of(5) {
+            Some(kind) => Ok(Self {
+                kind: kind.to_owned(),
+            }),
+            None => Err(NotThisKind),
         }
     }
 }
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct FourOfAKind {
     kind: Card,
     spare: SpareCards<1>,
 }
 
-impl PartialOrd for FourOfAKind {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        match self.kind.partial_cmp(&other.kind) {
-            Some(Ordering::Equal) => self.spare.partial_cmp(&other.spare),
+impl Ord for FourOfAKind {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.kind.cmp(&other.kind) {
+            Ordering::Equal => self.spare.cmp(&other.spare),
             neq => neq,
         }
     }
 }
 
+impl PartialOrd for FourOfAKind {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl FourOfAKind {
+    pub fn kind(&self) -> Card {
+        self.kind
+    }
+
+    pub fn spare(&self) -> &SpareCards<1> {
+        &self.spare
+    }
+}
+
+impl Display for FourOfAKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Four of a Kind: {} kicker {}", self.kind, self.spare)
+    }
+}
+
 impl TryFrom<&GenericHand> for FourOfAKind {
-    type Error = ();
+    type Error = NotThisKind;
 
     fn try_from(hand: &GenericHand) -> Result<Self, Self::Error> {
         let four_of = hand.find_n_of(4);
@@ -126,28 +264,52 @@ impl TryFrom<&GenericHand> for FourOfAKind {
                     },
                 })
             }
-            None => Err(()),
+            None => Err(NotThisKind),
         }
     }
 }
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct FullHouse {
     high_kind: Card,
     low_kind: Card,
 }
 
+impl Ord for FullHouse {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.high_kind.cmp(&other.high_kind) {
+            Ordering::Equal => self.low_kind.cmp(&other.low_kind),
+            neq => neq,
+        }
+    }
+}
+
 impl PartialOrd for FullHouse {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        match self.high_kind.partial_cmp(&other.high_kind) {
-            Some(Ordering::Equal) => self.low_kind.partial_cmp(&other.low_kind),
-            _ => self.high_kind.partial_cmp(&other.high_kind),
-        }
+        Some(self.cmp(other))
+    }
+}
+
+impl FullHouse {
+    /// The card there are three of.
+    pub fn high_kind(&self) -> Card {
+        self.high_kind
+    }
+
+    /// The card there are two of.
+    pub fn low_kind(&self) -> Card {
+        self.low_kind
+    }
+}
+
+impl Display for FullHouse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Full House: {},{}", self.high_kind, self.low_kind)
     }
 }
 
 impl TryFrom<&GenericHand> for FullHouse {
-    type Error = ();
+    type Error = NotThisKind;
 
     fn try_from(hand: &GenericHand) -> Result<Self, Self::Error> {
         let three_of = hand.find_n_of(3);
@@ -157,28 +319,50 @@ impl TryFrom<&GenericHand> for FullHouse {
                 high_kind: three_of.to_owned(),
                 low_kind: two_of.to_owned(),
             }),
-            _ => Err(()),
+            _ => Err(NotThisKind),
         }
     }
 }
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct ThreeOfAKind {
     kind: Card,
     spares: SpareCards<2>,
 }
 
-impl PartialOrd for ThreeOfAKind {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        match self.kind.partial_cmp(&other.kind) {
-            Some(Ordering::Equal) => self.spares.partial_cmp(&other.spares),
+impl Ord for ThreeOfAKind {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.kind.cmp(&other.kind) {
+            Ordering::Equal => self.spares.cmp(&other.spares),
             neq => neq,
         }
     }
 }
 
+impl PartialOrd for ThreeOfAKind {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl ThreeOfAKind {
+    pub fn kind(&self) -> Card {
+        self.kind
+    }
+
+    pub fn spares(&self) -> &SpareCards<2> {
+        &self.spares
+    }
+}
+
+impl Display for ThreeOfAKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Three of a Kind: {} kickers {}", self.kind, self.spares)
+    }
+}
+
 impl TryFrom<&GenericHand> for ThreeOfAKind {
-    type Error = ();
+    type Error = NotThisKind;
 
     fn try_from(value: &GenericHand) -> Result<Self, Self::Error> {
         let three_of = value.find_n_of(3);
@@ -193,23 +377,22 @@ impl TryFrom<&GenericHand> for ThreeOfAKind {
                 });
             }
         }
-        Err(())
+        Err(NotThisKind)
     }
 }
 
-#[derive(PartialEq, Clone, Debug)]
-
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct TwoPair {
     high_kind: Card,
     low_kind: Card,
     spares: SpareCards<1>,
 }
 
-impl PartialOrd for TwoPair {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        match self.high_kind.partial_cmp(&other.high_kind) {
-            Some(Ordering::Equal) => match self.low_kind.partial_cmp(&other.low_kind) {
-                Some(Ordering::Equal) => self.spares.partial_cmp(&other.spares),
+impl Ord for TwoPair {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.high_kind.cmp(&other.high_kind) {
+            Ordering::Equal => match self.low_kind.cmp(&other.low_kind) {
+                Ordering::Equal => self.spares.cmp(&other.spares),
                 neq => neq,
             },
             neq => neq,
@@ -217,8 +400,39 @@ impl PartialOrd for TwoPair {
     }
 }
 
+impl PartialOrd for TwoPair {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl TwoPair {
+    /// The higher of the two pairs.
+    pub fn high_kind(&self) -> Card {
+        self.high_kind
+    }
+
+    pub fn low_kind(&self) -> Card {
+        self.low_kind
+    }
+
+    pub fn spares(&self) -> &SpareCards<1> {
+        &self.spares
+    }
+}
+
+impl Display for TwoPair {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Two Pair: {},{} kicker {}",
+            self.high_kind, self.low_kind, self.spares
+        )
+    }
+}
+
 impl TryFrom<&GenericHand> for TwoPair {
-    type Error = ();
+    type Error = NotThisKind;
 
     fn try_from(value: &GenericHand) -> Result<Self, Self::Error> {
         let two_of = value.find_all_n_of(2);
@@ -234,27 +448,49 @@ impl TryFrom<&GenericHand> for TwoPair {
                 },
             });
         }
-        Err(())
+        Err(NotThisKind)
     }
 }
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct Pair {
     kind: Card,
     spares: SpareCards<3>,
 }
 
-impl PartialOrd for Pair {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        match self.kind.partial_cmp(&other.kind) {
-            Some(Ordering::Equal) => self.spares.partial_cmp(&other.spares),
+impl Ord for Pair {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.kind.cmp(&other.kind) {
+            Ordering::Equal => self.spares.cmp(&other.spares),
             neq => neq,
         }
     }
 }
 
+impl PartialOrd for Pair {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Pair {
+    pub fn kind(&self) -> Card {
+        self.kind
+    }
+
+    pub fn spares(&self) -> &SpareCards<3> {
+        &self.spares
+    }
+}
+
+impl Display for Pair {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "One Pair: {} kickers {}", self.kind, self.spares)
+    }
+}
+
 impl TryFrom<&GenericHand> for Pair {
-    type Error = ();
+    type Error = NotThisKind;
 
     fn try_from(value: &GenericHand) -> Result<Self, Self::Error> {
         let two_of = value.find_n_of(2);
@@ -273,23 +509,41 @@ impl TryFrom<&GenericHand> for Pair {
                 });
             }
         }
-        Err(())
+        Err(NotThisKind)
     }
 }
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct HighCard {
     spares: SpareCards<5>,
 }
 
+impl Ord for HighCard {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.spares.cmp(&other.spares)
+    }
+}
+
 impl PartialOrd for HighCard {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.spares.partial_cmp(&other.spares)
+        Some(self.cmp(other))
+    }
+}
+
+impl HighCard {
+    pub fn spares(&self) -> &SpareCards<5> {
+        &self.spares
+    }
+}
+
+impl Display for HighCard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "High Card: {}", self.spares)
     }
 }
 
 impl TryFrom<&GenericHand> for HighCard {
-    type Error = ();
+    type Error = NotThisKind;
 
     fn try_from(value: &GenericHand) -> Result<Self, Self::Error> {
         let remaining = value.find_all_n_of(1);
@@ -306,11 +560,12 @@ impl TryFrom<&GenericHand> for HighCard {
                 },
             });
         }
-        Err(())
+        Err(NotThisKind)
     }
 }
 
-#[derive(PartialEq, Clone, Debug)]
+/// Ordered by kind, then by the kind's own ranks and kickers.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
 pub enum GameHandKind {
     HighCard(HighCard),
     Pair(Pair),
@@ -321,25 +576,144 @@ pub enum GameHandKind {
     FiveOfAKind(FiveOfAKind),
 }
 
-#[derive(PartialEq, Clone, Debug)]
-pub struct GameHand {
-    kind: GameHandKind,
-    cards: [Card; 5],
+/// The kind of a hand without its cards, weakest first.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub enum HandCategory {
+    HighCard,
+    Pair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
 }
 
-impl GameHand {
-    fn hand_order(&self) -> usize {
-        match self.kind {
-            GameHandKind::HighCard(_) => 0,
-            GameHandKind::Pair(_) => 1,
-            GameHandKind::TwoPair(_) => 2,
-            GameHandKind::ThreeOfAKind(_) => 3,
-            GameHandKind::FullHouse(_) => 4,
-            GameHandKind::FourOfAKind(_) => 5,
-            GameHandKind::FiveOfAKind(_) => 6,
+impl HandCategory {
+    /// Every category, weakest first, so that `HandCategory::ALL[c as usize] == c`.
+    pub const ALL: [HandCategory; 7] = [
+        Self::HighCard,
+        Self::Pair,
+        Self::TwoPair,
+        Self::ThreeOfAKind,
+        Self::FullHouse,
+        Self::FourOfAKind,
+        Self::FiveOfAKind,
+    ];
+}
+
+impl Display for HandCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            HandCategory::HighCard => "High Card",
+            HandCategory::Pair => "One Pair",
+            HandCategory::TwoPair => "Two Pair",
+            HandCategory::ThreeOfAKind => "Three of a Kind",
+            HandCategory::FullHouse => "Full House",
+            HandCategory::FourOfAKind => "Four of a Kind",
+            HandCategory::FiveOfAKind => "Five of a Kind",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl GameHandKind {
+    pub fn category(&self) -> HandCategory {
+        match self {
+            GameHandKind::HighCard(_) => HandCategory::HighCard,
+            GameHandKind::Pair(_) => HandCategory::Pair,
+            GameHandKind::TwoPair(_) => HandCategory::TwoPair,
+            GameHandKind::ThreeOfAKind(_) => HandCategory::ThreeOfAKind,
+            GameHandKind::FullHouse(_) => HandCategory::FullHouse,
+            GameHandKind::FourOfAKind(_) => HandCategory::FourOfAKind,
+            GameHandKind::FiveOfAKind(_) => HandCategory::FiveOfAKind,
+        }
+    }
+
+    /// The cards the kind is made of, larger group first. A high card hand
+    /// has none.
+    pub fn defining_ranks(&self) -> Vec<Card> {
+        match self {
+            GameHandKind::HighCard(_) => vec![],
+            GameHandKind::Pair(kind) => vec![kind.kind],
+            GameHandKind::TwoPair(kind) => vec![kind.high_kind, kind.low_kind],
+            GameHandKind::ThreeOfAKind(kind) => vec![kind.kind],
+            GameHandKind::FullHouse(kind) => vec![kind.high_kind, kind.low_kind],
+            GameHandKind::FourOfAKind(kind) => vec![kind.kind],
+            GameHandKind::FiveOfAKind(kind) => vec![kind.kind],
         }
     }
 
+    /// The cards left over, highest first.
+    pub fn kickers(&self) -> Vec<Card> {
+        let mut kickers = match self {
+            GameHandKind::HighCard(kind) => kind.spares.cards.to_vec(),
+            GameHandKind::Pair(kind) => kind.spares.cards.to_vec(),
+            GameHandKind::TwoPair(kind) => kind.spares.cards.to_vec(),
+            GameHandKind::ThreeOfAKind(kind) => kind.spares.cards.to_vec(),
+            GameHandKind::FullHouse(_) => vec![],
+            GameHandKind::FourOfAKind(kind) => kind.spare.cards.to_vec(),
+            GameHandKind::FiveOfAKind(_) => vec![],
+        };
+        kickers.sort_by(|a, b| b.cmp(a));
+        kickers
+    }
+}
+
+impl Display for GameHandKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameHandKind::HighCard(kind) => write!(f, "{}", kind),
+            GameHandKind::Pair(kind) => write!(f, "{}", kind),
+            GameHandKind::TwoPair(kind) => write!(f, "{}", kind),
+            GameHandKind::ThreeOfAKind(kind) => write!(f, "{}", kind),
+            GameHandKind::FullHouse(kind) => write!(f, "{}", kind),
+            GameHandKind::FourOfAKind(kind) => write!(f, "{}", kind),
+            GameHandKind::FiveOfAKind(kind) => write!(f, "{}", kind),
+        }
+    }
+}
+
+/// How to order two hands of the same kind. Camel Cards compares the cards
+/// one by one in the order they were dealt; poker compares the ranks that
+/// make up the kind first, then the kickers from highest down.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum TieBreak {
+    #[default]
+    Positional,
+    Kicker,
+}
+
+/// A name that isn't any tie-break rule.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct UnknownTieBreak(pub String);
+
+impl Display for UnknownTieBreak {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown tie-break rule {:?}", self.0)
+    }
+}
+
+impl Error for UnknownTieBreak {}
+
+impl FromStr for TieBreak {
+    type Err = UnknownTieBreak;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "positional" | "camel" => Ok(Self::Positional),
+            "kicker" | "poker" => Ok(Self::Kicker),
+            _ => Err(UnknownTieBreak(value.to_string())),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct GameHand {
+    kind: GameHandKind,
+    pub(crate) cards: [Card; 5],
+}
+
+impl GameHand {
     fn cmp_with_other_by_cards(&self, other: &GameHand) -> Ordering {
         let self_cards: Vec<_> = self.cards.iter().collect();
         let other_cards: Vec<_> = other.cards.iter().collect();
@@ -358,91 +732,135 @@ impl GameHand {
     }
 }
 
+impl GameHand {
+    /// See `GenericHand::parse`.
+    pub fn parse(value: &str, rules: CardRules) -> Result<Self, HandError> {
+        Self::try_from((value, rules))
+    }
+
+    pub fn kind(&self) -> &GameHandKind {
+        &self.kind
+    }
+
+    /// The cards in the order they were dealt.
+    pub fn cards(&self) -> &[Card; 5] {
+        &self.cards
+    }
+
+    pub fn category(&self) -> HandCategory {
+        self.kind.category()
+    }
+
+    /// See `GameHandKind::defining_ranks`.
+    pub fn defining_ranks(&self) -> Vec<Card> {
+        self.kind.defining_ranks()
+    }
+
+    /// See `GameHandKind::kickers`.
+    pub fn kickers(&self) -> Vec<Card> {
+        self.kind.kickers()
+    }
+
+    /// A number that orders hands the same way `Ord` does. It's stable
+    /// across versions: the category as in `HandCategory::ALL` in bits 20 and
+    /// up, then 4 bits for each card's `Card::rank` in the order dealt.
+    pub fn strength(&self) -> u32 {
+        HandKey::from(self).value()
+    }
+
+    /// Compares by kind, then settles ties using `tie_break`. The `Ord` impl
+    /// is the same as `TieBreak::Positional`.
+    pub fn cmp_by(&self, other: &GameHand, tie_break: TieBreak) -> Ordering {
+        match tie_break {
+            TieBreak::Positional => self.cmp(other),
+            TieBreak::Kicker => self.kind.cmp(&other.kind),
+        }
+    }
+}
+
 impl TryFrom<&GenericHand> for GameHand {
-    type Error = ();
+    type Error = HandError;
 
     fn try_from(unknown: &GenericHand) -> Result<Self, Self::Error> {
-        let five_of_kind = FiveOfAKind::try_from(unknown);
-        if five_of_kind.is_ok() {
-            let kind = GameHandKind::FiveOfAKind(five_of_kind.unwrap());
-            return Ok(Self {
-                kind,
-                cards: unknown.cards,
-            });
-        }
-        let four_of_kind = FourOfAKind::try_from(unknown);
-        if four_of_kind.is_ok() {
-            let kind = GameHandKind::FourOfAKind(four_of_kind.unwrap());
-            return Ok(Self {
-                kind,
-                cards: unknown.cards,
-            });
-        }
-        let full_house = FullHouse::try_from(unknown);
-        if full_house.is_ok() {
-            let kind = GameHandKind::FullHouse(full_house.unwrap());
-            return Ok(Self {
-                kind,
-                cards: unknown.cards,
-            });
-        }
-        let three_of_kind = ThreeOfAKind::try_from(unknown);
-        if three_of_kind.is_ok() {
-            let kind = GameHandKind::ThreeOfAKind(three_of_kind.unwrap());
-            return Ok(Self {
-                kind,
-                cards: unknown.cards,
-            });
-        }
-        let two_pair = TwoPair::try_from(unknown);
-        if two_pair.is_ok() {
-            let kind = GameHandKind::TwoPair(two_pair.unwrap());
-            return Ok(Self {
-                kind,
-                cards: unknown.cards,
-            });
-        }
-        let pair = Pair::try_from(unknown);
-        if pair.is_ok() {
-            let kind = GameHandKind::Pair(pair.unwrap());
-            return Ok(Self {
-                kind,
-                cards: unknown.cards,
-            });
-        }
-        let high_card = HighCard::try_from(unknown);
-        if high_card.is_ok() {
-            let kind = GameHandKind::HighCard(high_card.unwrap());
-            return Ok(Self {
-                kind,
-                cards: unknown.cards,
-            });
-        }
-        Err(())
+        let kind = if let Ok(five_of_kind) = FiveOfAKind::try_from(unknown) {
+            GameHandKind::FiveOfAKind(five_of_kind)
+        } else if let Ok(four_of_kind) = FourOfAKind::try_from(unknown) {
+            GameHandKind::FourOfAKind(four_of_kind)
+        } else if let Ok(full_house) = FullHouse::try_from(unknown) {
+            GameHandKind::FullHouse(full_house)
+        } else if let Ok(three_of_kind) = ThreeOfAKind::try_from(unknown) {
+            GameHandKind::ThreeOfAKind(three_of_kind)
+        } else if let Ok(two_pair) = TwoPair::try_from(unknown) {
+            GameHandKind::TwoPair(two_pair)
+        } else if let Ok(pair) = Pair::try_from(unknown) {
+            GameHandKind::Pair(pair)
+        } else if let Ok(high_card) = HighCard::try_from(unknown) {
+            GameHandKind::HighCard(high_card)
+        } else {
+            unreachable!("Every hand is at least a high card")
+        };
+        Ok(Self {
+            kind,
+            cards: unknown.cards,
+        })
+    }
+}
+
+impl TryFrom<(&str, CardRules)> for GameHand {
+    type Error = HandError;
+
+    fn try_from(value: (&str, CardRules)) -> Result<Self, Self::Error> {
+        Self::try_from(&GenericHand::try_from(value)?)
     }
 }
 
 impl TryFrom<&str> for GameHand {
-    type Error = ();
+    type Error = HandError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let unknown = GenericHand::try_from(value);
-        if unknown.is_err() {
-            return Err(());
+        Self::try_from((value, CardRules::Standard))
+    }
+}
+
+/// The cards as dealt, like `KK677`. The alternate form `{:#}` also names
+/// the kind, like `KK677 (Two Pair: K,7 kicker 6)`.
+impl Display for GameHand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for card in &self.cards {
+            write!(f, "{}", card)?;
         }
-        Self::try_from(&unknown.unwrap())
+        if f.alternate() {
+            write!(f, " ({})", self.kind)?;
+        }
+        Ok(())
     }
 }
 
-impl PartialOrd for GameHand {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        match self.hand_order().partial_cmp(&other.hand_order()) {
-            Some(Ordering::Equal) => Some(self.cmp_with_other_by_cards(&other)),
+/// Reads a hand under `CardRules::Standard`, where `J` is always a jack.
+/// Use `GameHand::parse` to read jokers back.
+impl FromStr for GameHand {
+    type Err = HandError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+impl Ord for GameHand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.category().cmp(&other.category()) {
+            Ordering::Equal => self.cmp_with_other_by_cards(other),
             neq => neq,
         }
     }
 }
 
+impl PartialOrd for GameHand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -465,7 +883,7 @@ mod tests {
             Card::Seven,
         ]);
         let five_of_kind = FiveOfAKind::try_from(&hand);
-        assert!(five_of_kind.is_err());
+        assert_eq!(five_of_kind.unwrap_err(), NotThisKind);
     }
 
     #[test]
@@ -786,4 +1204,218 @@ mod tests {
         assert!(second < third);
         assert!((first < second) && (second < third));
     }
+
+    #[test]
+    fn test_jokers_count_as_best_card() {
+        let game_hand = GameHand::try_from(("T55J5", CardRules::JokersWild)).unwrap();
+        assert!(matches!(game_hand.kind, GameHandKind::FourOfAKind(_)));
+        let game_hand = GameHand::try_from(("KTJJT", CardRules::JokersWild)).unwrap();
+        assert!(matches!(game_hand.kind, GameHandKind::FourOfAKind(_)));
+        let game_hand = GameHand::try_from(("2345J", CardRules::JokersWild)).unwrap();
+        assert!(matches!(game_hand.kind, GameHandKind::Pair(_)));
+        let game_hand = GameHand::try_from(("JJJJJ", CardRules::JokersWild)).unwrap();
+        assert!(matches!(game_hand.kind, GameHandKind::FiveOfAKind(_)));
+    }
+
+    #[test]
+    fn test_jokers_standard_rules_unchanged() {
+        let game_hand = GameHand::try_from(("T55J5", CardRules::Standard)).unwrap();
+        assert!(matches!(game_hand.kind, GameHandKind::ThreeOfAKind(_)));
+    }
+
+    #[test]
+    fn test_cmp_jokers_rank_lowest() {
+        let first = GameHand::try_from(("JKKK2", CardRules::JokersWild)).unwrap();
+        let second = GameHand::try_from(("QQQQ2", CardRules::JokersWild)).unwrap();
+        let third = GameHand::try_from(("JJJJJ", CardRules::JokersWild)).unwrap();
+        let fourth = GameHand::try_from(("22222", CardRules::JokersWild)).unwrap();
+        assert!(first < second);
+        assert!(third < fourth);
+    }
+
+    #[test]
+    fn test_sort_matches_partial_cmp() {
+        let mut sorted: Vec<GameHand> = include_str!("../input_test.txt")
+            .lines()
+            .map(|line| GameHand::try_from(&line[..5]).unwrap())
+            .collect();
+        sorted.sort();
+        for pair in sorted.windows(2) {
+            assert!(pair[0].partial_cmp(&pair[1]) != Some(Ordering::Greater));
+        }
+        assert_eq!(sorted.first(), Some(&GameHand::try_from("2345J").unwrap()));
+        assert_eq!(sorted.last(), Some(&GameHand::try_from("AAAAA").unwrap()));
+    }
+
+    #[test]
+    fn test_hands_in_collections() {
+        use std::collections::{BTreeMap, BinaryHeap};
+        let hands = ["KK677", "T55J5", "32T3K", "QQQJA", "KTJJT"];
+        let mut heap: BinaryHeap<GameHand> = hands
+            .iter()
+            .map(|h| GameHand::try_from(*h).unwrap())
+            .collect();
+        assert_eq!(heap.pop(), Some(GameHand::try_from("QQQJA").unwrap()));
+        let bids: BTreeMap<GameHand, usize> = hands
+            .iter()
+            .enumerate()
+            .map(|(bid, h)| (GameHand::try_from(*h).unwrap(), bid))
+            .collect();
+        let ranked: Vec<usize> = bids.values().copied().collect();
+        assert_eq!(ranked, vec![2, 4, 0, 1, 3]);
+    }
+
+    #[test]
+    fn test_kind_ordering() {
+        let pair = GameHand::try_from("KK677").unwrap();
+        let two_pair = GameHand::try_from("KTTK2").unwrap();
+        assert!(pair.kind < two_pair.kind);
+        let low_four = FourOfAKind::try_from(&GenericHand::try_from("7777A").unwrap()).unwrap();
+        let high_four = FourOfAKind::try_from(&GenericHand::try_from("8888K").unwrap()).unwrap();
+        assert!(low_four < high_four);
+        assert_eq!(low_four.cmp(&low_four.clone()), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_tie_break_rulesets_disagree() {
+        // Same kind, but the first card favours one hand and the pair the other.
+        let a = GameHand::try_from("A2233").unwrap();
+        let b = GameHand::try_from("K4433").unwrap();
+        assert_eq!(a.cmp_by(&b, TieBreak::Positional), Ordering::Greater);
+        assert_eq!(a.cmp_by(&b, TieBreak::Kicker), Ordering::Less);
+        let c = GameHand::try_from("2KK34").unwrap();
+        let d = GameHand::try_from("KK234").unwrap();
+        assert_eq!(c.cmp_by(&d, TieBreak::Positional), Ordering::Less);
+        assert_eq!(c.cmp_by(&d, TieBreak::Kicker), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_tie_break_kind_always_first() {
+        let pair = GameHand::try_from("AAKQT").unwrap();
+        let two_pair = GameHand::try_from("22334").unwrap();
+        for tie_break in [TieBreak::Positional, TieBreak::Kicker] {
+            assert_eq!(pair.cmp_by(&two_pair, tie_break), Ordering::Less);
+        }
+    }
+
+    #[test]
+    fn test_tie_break_kicker_with_jokers() {
+        // Jokers join the most common card, so they count towards its rank.
+        let a = GameHand::try_from(("JQQ23", CardRules::JokersWild)).unwrap();
+        let b = GameHand::try_from(("KKK23", CardRules::JokersWild)).unwrap();
+        assert_eq!(a.cmp_by(&b, TieBreak::Kicker), Ordering::Less);
+        let c = GameHand::try_from(("QJQ23", CardRules::JokersWild)).unwrap();
+        assert_eq!(a.cmp_by(&c, TieBreak::Kicker), Ordering::Equal);
+        assert_eq!(a.cmp_by(&c, TieBreak::Positional), Ordering::Less);
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        for line in include_str!("../input_test.txt").lines() {
+            let text = &line[..5];
+            assert_eq!(text.parse::<GameHand>().unwrap().to_string(), text);
+            assert_eq!(text.parse::<GenericHand>().unwrap().to_string(), text);
+            let wild = GameHand::parse(text, CardRules::JokersWild).unwrap();
+            assert_eq!(wild.to_string(), text);
+            let reread = GameHand::parse(&wild.to_string(), CardRules::JokersWild);
+            assert_eq!(reread.unwrap(), wild);
+        }
+        assert_eq!(
+            "KK67".parse::<GameHand>(),
+            Err(HandError::WrongLength {
+                expected: 5,
+                found: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_joker_round_trip() {
+        let wild = GameHand::parse("KTJJT", CardRules::JokersWild).unwrap();
+        assert_eq!(wild.category(), HandCategory::FourOfAKind);
+        let text = wild.to_string();
+        assert_eq!(text, "KTJJT");
+        assert_eq!(GameHand::parse(&text, CardRules::JokersWild).unwrap(), wild);
+        let generic = GenericHand::<5>::parse(&text, CardRules::JokersWild).unwrap();
+        assert_eq!(generic.cards, *wild.cards());
+        // Without the rules the jokers come back as jacks.
+        let standard = text.parse::<GameHand>().unwrap();
+        assert_eq!(standard.cards()[2], Card::Jack);
+        assert_eq!(standard.category(), HandCategory::TwoPair);
+        assert_ne!(standard, wild);
+    }
+
+    #[test]
+    fn test_verbose_display() {
+        let cases = [
+            ("KK677", "KK677 (Two Pair: K,7 kicker 6)"),
+            ("AAAAA", "AAAAA (Five of a Kind: A)"),
+            ("7AAAA", "7AAAA (Four of a Kind: A kicker 7)"),
+            ("QQ3Q3", "QQ3Q3 (Full House: Q,3)"),
+            ("T55J5", "T55J5 (Three of a Kind: 5 kickers J,T)"),
+            ("32T3K", "32T3K (One Pair: 3 kickers K,T,2)"),
+            ("2345A", "2345A (High Card: A,5,4,3,2)"),
+        ];
+        for (text, verbose) in cases {
+            assert_eq!(format!("{:#}", text.parse::<GameHand>().unwrap()), verbose);
+        }
+        let wild = GameHand::try_from(("T55J5", CardRules::JokersWild)).unwrap();
+        assert_eq!(format!("{:#}", wild), "T55J5 (Four of a Kind: 5 kicker T)");
+    }
+
+    #[test]
+    fn test_introspection() {
+        let hand: GameHand = "KK677".parse().unwrap();
+        assert_eq!(hand.category(), HandCategory::TwoPair);
+        assert_eq!(hand.defining_ranks(), vec![Card::King, Card::Seven]);
+        assert_eq!(hand.kickers(), vec![Card::Six]);
+        assert_eq!(hand.cards()[2], Card::Six);
+        let GameHandKind::TwoPair(two_pair) = hand.kind() else {
+            panic!("expected two pair");
+        };
+        assert_eq!(two_pair.low_kind(), Card::Seven);
+
+        let hand: GameHand = "T55J5".parse().unwrap();
+        assert_eq!(hand.category(), HandCategory::ThreeOfAKind);
+        assert_eq!(hand.kickers(), vec![Card::Jack, Card::Ten]);
+        let wild = GameHand::try_from(("T55J5", CardRules::JokersWild)).unwrap();
+        assert_eq!(wild.category(), HandCategory::FourOfAKind);
+        assert_eq!(wild.defining_ranks(), vec![Card::Five]);
+        assert_eq!(wild.kickers(), vec![Card::Ten]);
+
+        let hand: GameHand = "QQ3Q3".parse().unwrap();
+        assert_eq!(hand.defining_ranks(), vec![Card::Queen, Card::Three]);
+        assert!(hand.kickers().is_empty());
+        let hand: GameHand = "2345A".parse().unwrap();
+        assert!(hand.defining_ranks().is_empty());
+        assert_eq!(hand.kickers()[0], Card::Ace);
+    }
+
+    #[test]
+    fn test_strength() {
+        let hand: GameHand = "KK677".parse().unwrap();
+        assert_eq!(hand.strength(), 0x2CC566);
+        assert_eq!("AAAAA".parse::<GameHand>().unwrap().strength(), 0x6DDDDD);
+        let mut hands: Vec<GameHand> = include_str!("../input_test.txt")
+            .lines()
+            .map(|line| line[..5].parse().unwrap())
+            .collect();
+        hands.sort();
+        for pair in hands.windows(2) {
+            assert!(pair[0].strength() < pair[1].strength());
+        }
+        for (idx, category) in HandCategory::ALL.iter().enumerate() {
+            assert_eq!(*category as usize, idx);
+        }
+    }
+
+    #[test]
+    fn test_tie_break_from_str() {
+        assert_eq!("camel".parse(), Ok(TieBreak::Positional));
+        assert_eq!("kicker".parse(), Ok(TieBreak::Kicker));
+        assert_eq!(
+            "high".parse::<TieBreak>(),
+            Err(UnknownTieBreak("high".to_string()))
+        );
+    }
 }
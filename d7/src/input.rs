@@ -0,0 +1,191 @@
+use crate::cards::CardRules;
+use crate::hands::{GameHand, HandError};
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandWithBid {
+    pub hand: GameHand,
+    pub bid: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineErrorKind {
+    Hand(HandError),
+    MissingBid,
+    BadBid(String),
+}
+
+/// A line of input that isn't a hand followed by its bid. Lines count from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineError {
+    pub line: usize,
+    pub kind: LineErrorKind,
+}
+
+impl Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            LineErrorKind::Hand(err) => write!(f, "{}", err),
+            LineErrorKind::MissingBid => write!(f, "missing bid"),
+            LineErrorKind::BadBid(bid) => write!(f, "invalid bid {:?}", bid),
+        }
+    }
+}
+
+impl Error for LineError {}
+
+/// What to do with a line that can't be read.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum BadLines {
+    #[default]
+    Reject,
+    Skip,
+}
+
+/// A name that isn't any way of handling bad lines.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct UnknownBadLines(pub String);
+
+impl Display for UnknownBadLines {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown bad line handling {:?}", self.0)
+    }
+}
+
+impl Error for UnknownBadLines {}
+
+impl FromStr for BadLines {
+    type Err = UnknownBadLines;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "reject" => Ok(Self::Reject),
+            "skip" => Ok(Self::Skip),
+            _ => Err(UnknownBadLines(value.to_string())),
+        }
+    }
+}
+
+pub fn parse_line(line: &str, rules: CardRules) -> Result<HandWithBid, LineErrorKind> {
+    let line = line.trim();
+    let (hand, bid) = match line.split_once(char::is_whitespace) {
+        Some((hand, bid)) => (hand, bid.trim()),
+        None => (line, ""),
+    };
+    let hand = GameHand::try_from((hand, rules)).map_err(LineErrorKind::Hand)?;
+    if bid.is_empty() {
+        return Err(LineErrorKind::MissingBid);
+    }
+    let bid = bid
+        .parse::<u64>()
+        .map_err(|_| LineErrorKind::BadBid(bid.to_string()))?;
+    Ok(HandWithBid { hand, bid })
+}
+
+/// Reads a hand and bid from each non-blank line, in input order. Under
+/// `BadLines::Skip` the lines that couldn't be read are returned alongside
+/// the hands instead of failing the whole input.
+pub fn parse_hands_with_bids(
+    input: &str,
+    rules: CardRules,
+    bad_lines: BadLines,
+) -> Result<(Vec<HandWithBid>, Vec<LineError>), LineError> {
+    let mut hands = Vec::new();
+    let mut skipped = Vec::new();
+    for (idx, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_line(line, rules) {
+            Ok(hand) => hands.push(hand),
+            Err(kind) => {
+                let err = LineError {
+                    line: idx + 1,
+                    kind,
+                };
+                match bad_lines {
+                    BadLines::Reject => return Err(err),
+                    BadLines::Skip => skipped.push(err),
+                }
+            }
+        }
+    }
+    Ok((hands, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line_error(line: &str) -> LineErrorKind {
+        parse_line(line, CardRules::Standard).unwrap_err()
+    }
+
+    #[test]
+    fn test_parse_line() {
+        let parsed = parse_line("32T3K 765", CardRules::Standard).unwrap();
+        assert_eq!(parsed.bid, 765);
+        assert_eq!(parsed.hand, GameHand::try_from("32T3K").unwrap());
+    }
+
+    #[test]
+    fn test_line_errors() {
+        assert_eq!(
+            line_error("32X3K 765"),
+            LineErrorKind::Hand(HandError::InvalidCard {
+                position: 3,
+                card: 'X'
+            })
+        );
+        assert_eq!(
+            line_error("32T3 765"),
+            LineErrorKind::Hand(HandError::WrongLength {
+                expected: 5,
+                found: 4
+            })
+        );
+        assert_eq!(line_error("32T3K"), LineErrorKind::MissingBid);
+        assert_eq!(
+            line_error("32T3K 7x5"),
+            LineErrorKind::BadBid("7x5".to_string())
+        );
+        assert_eq!(
+            line_error("32T3K -5"),
+            LineErrorKind::BadBid("-5".to_string())
+        );
+    }
+
+    #[test]
+    fn test_bad_lines() {
+        let input = "32T3K 765\nT55J5\n\nKK677 28\n";
+        let err = parse_hands_with_bids(input, CardRules::Standard, BadLines::Reject).unwrap_err();
+        assert_eq!(err.to_string(), "line 2: missing bid");
+        let (hands, skipped) =
+            parse_hands_with_bids(input, CardRules::Standard, BadLines::Skip).unwrap();
+        assert_eq!(hands.len(), 2);
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].line, 2);
+    }
+
+    #[test]
+    fn test_bad_lines_from_str() {
+        assert_eq!("skip".parse(), Ok(BadLines::Skip));
+        assert_eq!("reject".parse(), Ok(BadLines::Reject));
+        assert_eq!(
+            "ignore".parse::<BadLines>(),
+            Err(UnknownBadLines("ignore".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_error_display() {
+        let err = LineError {
+            line: 4,
+            kind: line_error("AAQ1K 3"),
+        };
+        assert_eq!(err.to_string(), "line 4: invalid card '1' at position 4");
+    }
+}
@@ -1,2 +1,7 @@
 pub mod cards;
+pub mod encoding;
+pub mod equity;
 pub mod hands;
+pub mod input;
+pub mod poker;
+pub mod sized;
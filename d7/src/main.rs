@@ -1,36 +1,78 @@
-use d7::hands::GameHand;
-use std::fs;
+use d7::cards::CardRules;
+use d7::hands::TieBreak;
+use d7::input::{parse_hands_with_bids, BadLines, HandWithBid, LineError};
+use std::{env, error::Error, fs, process};
 
-#[derive(Debug)]
-struct HandWithBid {
-    hand: GameHand,
-    bid: u64,
-}
-
-fn parse_input(input: &str) -> Vec<HandWithBid> {
-    let mut hands_with_bid: Vec<_> = input
-        .lines()
-        .map(|line| {
-            let split: Vec<_> = line.split(" ").collect();
-            let hand = GameHand::try_from(split[0]).unwrap();
-            let bid = split[1].parse::<u64>().unwrap();
-            HandWithBid { hand, bid }
-        })
-        .collect();
-    hands_with_bid.sort_by(|a, b| a.hand.partial_cmp(&b.hand).unwrap());
-    hands_with_bid
+fn parse_input(
+    input: &str,
+    rules: CardRules,
+    tie_break: TieBreak,
+    bad_lines: BadLines,
+) -> Result<Vec<HandWithBid>, LineError> {
+    let (mut hands_with_bid, skipped) = parse_hands_with_bids(input, rules, bad_lines)?;
+    for err in skipped {
+        eprintln!("input.txt: skipping {}", err);
+    }
+    hands_with_bid.sort_by(|a, b| a.hand.cmp_by(&b.hand, tie_break));
+    Ok(hands_with_bid)
 }
 
-fn part_1(hands_with_bids: &[HandWithBid]) {
+fn total_winnings(hands_with_bids: &[HandWithBid]) -> u64 {
     let mut total: u64 = 0;
     for (mult, hand) in hands_with_bids.iter().enumerate() {
         total += hand.bid * (mult + 1) as u64;
     }
-    println!("Part 1: {}", total)
+    total
+}
+
+fn part_1(input: &str, tie_break: TieBreak, bad_lines: BadLines) -> Result<(), LineError> {
+    let hands_with_bids = parse_input(input, CardRules::Standard, tie_break, bad_lines)?;
+    println!("Part 1: {}", total_winnings(&hands_with_bids));
+    Ok(())
+}
+
+fn part_2(input: &str, tie_break: TieBreak, bad_lines: BadLines) -> Result<(), LineError> {
+    let hands_with_bids = parse_input(input, CardRules::JokersWild, tie_break, bad_lines)?;
+    println!("Part 2: {}", total_winnings(&hands_with_bids));
+    Ok(())
+}
+
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Result<Option<&'a str>, String> {
+    match args.iter().position(|arg| arg == flag) {
+        Some(i) => match args.get(i + 1) {
+            Some(value) => Ok(Some(value.as_str())),
+            None => Err(format!("{} needs a value", flag)),
+        },
+        None => Ok(None),
+    }
+}
+
+/// `--tie-break kicker` ranks hands of the same kind the poker way instead
+/// of by the order their cards were dealt, and `--bad-lines skip` leaves
+/// out lines that can't be read instead of stopping at the first.
+fn options(args: &[String]) -> Result<(TieBreak, BadLines), Box<dyn Error>> {
+    let tie_break = match flag_value(args, "--tie-break")? {
+        Some(value) => value.parse()?,
+        None => TieBreak::default(),
+    };
+    let bad_lines = match flag_value(args, "--bad-lines")? {
+        Some(value) => value.parse()?,
+        None => BadLines::default(),
+    };
+    Ok((tie_break, bad_lines))
 }
 
 fn main() {
+    let args: Vec<String> = env::args().collect();
+    let (tie_break, bad_lines) = options(&args).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
     let input = fs::read_to_string("input.txt").unwrap();
-    let hands_with_bids = parse_input(&input);
-    part_1(&hands_with_bids);
+    let result =
+        part_1(&input, tie_break, bad_lines).and_then(|_| part_2(&input, tie_break, bad_lines));
+    if let Err(err) = result {
+        eprintln!("input.txt: {}", err);
+        process::exit(1);
+    }
 }
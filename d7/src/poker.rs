@@ -0,0 +1,328 @@
+use crate::cards::{parse_suited_cards, Card, SpareCards, SuitedCard, SuitedCardError};
+use crate::hands::{FourOfAKind, FullHouse, GenericHand, HighCard, Pair, ThreeOfAKind, TwoPair};
+use std::cmp::{Ord, Ordering, PartialEq, PartialOrd};
+use std::error::Error;
+use std::fmt::{self, Display};
+
+/// Cards that can't be made into a poker hand.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum PokerHandError {
+    Card(SuitedCardError),
+    /// A joker given a suit, which a standard deck doesn't have.
+    SuitedJoker,
+    DuplicateCard(SuitedCard),
+    WrongCardCount {
+        min: usize,
+        max: usize,
+        found: usize,
+    },
+}
+
+impl Display for PokerHandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Card(err) => write!(f, "{}", err),
+            Self::SuitedJoker => write!(f, "jokers can't be suited"),
+            Self::DuplicateCard(card) => write!(f, "duplicate card {}", card),
+            Self::WrongCardCount { min, max, found } if min == max => {
+                write!(f, "expected {} cards, found {}", min, found)
+            }
+            Self::WrongCardCount { min, max, found } => {
+                write!(f, "expected {} to {} cards, found {}", min, max, found)
+            }
+        }
+    }
+}
+
+impl Error for PokerHandError {}
+
+impl From<SuitedCardError> for PokerHandError {
+    fn from(err: SuitedCardError) -> Self {
+        Self::Card(err)
+    }
+}
+
+/// Five cards in sequence, ranked by the highest. The wheel (A-2-3-4-5) is the
+/// lowest straight, with the ace playing low.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
+pub struct Straight {
+    high: Card,
+}
+
+impl Straight {
+    fn find(cards: &[Card; 5]) -> Option<Self> {
+        let mut ranks: Vec<_> = cards.iter().map(|card| card.rank()).collect();
+        ranks.sort();
+        ranks.dedup();
+        if ranks.len() != 5 {
+            return None;
+        }
+        if ranks[4] - ranks[0] == 4 {
+            return Card::from_rank(ranks[4]).map(|high| Self { high });
+        }
+        let wheel = [Card::Two, Card::Three, Card::Four, Card::Five, Card::Ace];
+        if ranks == wheel.map(|card| card.rank()) {
+            return Some(Self { high: Card::Five });
+        }
+        None
+    }
+}
+
+/// Five cards of one suit, ranked like a high card hand.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
+pub struct Flush {
+    spares: SpareCards<5>,
+}
+
+/// Ordered by kind, then by the kind's own ranks and kickers. Without jokers
+/// there's no five of a kind.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
+pub enum PokerHandKind {
+    HighCard(HighCard),
+    Pair(Pair),
+    TwoPair(TwoPair),
+    ThreeOfAKind(ThreeOfAKind),
+    Straight(Straight),
+    Flush(Flush),
+    FullHouse(FullHouse),
+    FourOfAKind(FourOfAKind),
+    StraightFlush(Straight),
+}
+
+/// A five card poker hand. Two hands are equal when they rank the same,
+/// whatever their suits.
+#[derive(Clone, Debug)]
+pub struct PokerHand {
+    pub kind: PokerHandKind,
+    pub cards: [SuitedCard; 5],
+}
+
+impl TryFrom<&[SuitedCard; 5]> for PokerHand {
+    type Error = PokerHandError;
+
+    fn try_from(cards: &[SuitedCard; 5]) -> Result<Self, Self::Error> {
+        for (idx, card) in cards.iter().enumerate() {
+            if card.card == Card::Joker {
+                return Err(PokerHandError::SuitedJoker);
+            }
+            if cards[idx + 1..].contains(card) {
+                return Err(PokerHandError::DuplicateCard(*card));
+            }
+        }
+        let ranks = cards.map(|card| card.card);
+        let flush = cards.iter().all(|card| card.suit == cards[0].suit);
+        let straight = Straight::find(&ranks);
+        let hand = GenericHand::new(&ranks);
+        let kind = if let (Some(straight), true) = (&straight, flush) {
+            PokerHandKind::StraightFlush(straight.to_owned())
+        } else if let Ok(four_of_kind) = FourOfAKind::try_from(&hand) {
+            PokerHandKind::FourOfAKind(four_of_kind)
+        } else if let Ok(full_house) = FullHouse::try_from(&hand) {
+            PokerHandKind::FullHouse(full_house)
+        } else if flush {
+            PokerHandKind::Flush(Flush {
+                spares: SpareCards { cards: ranks },
+            })
+        } else if let Some(straight) = straight {
+            PokerHandKind::Straight(straight)
+        } else if let Ok(three_of_kind) = ThreeOfAKind::try_from(&hand) {
+            PokerHandKind::ThreeOfAKind(three_of_kind)
+        } else if let Ok(two_pair) = TwoPair::try_from(&hand) {
+            PokerHandKind::TwoPair(two_pair)
+        } else if let Ok(pair) = Pair::try_from(&hand) {
+            PokerHandKind::Pair(pair)
+        } else if let Ok(high_card) = HighCard::try_from(&hand) {
+            PokerHandKind::HighCard(high_card)
+        } else {
+            unreachable!("Five distinct cards always make a hand")
+        };
+        Ok(Self {
+            kind,
+            cards: cards.to_owned(),
+        })
+    }
+}
+
+impl TryFrom<&str> for PokerHand {
+    type Error = PokerHandError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let cards = parse_suited_cards(value)?;
+        let found = cards.len();
+        let cards: [SuitedCard; 5] =
+            cards
+                .try_into()
+                .map_err(|_| PokerHandError::WrongCardCount {
+                    min: 5,
+                    max: 5,
+                    found,
+                })?;
+        Self::try_from(&cards)
+    }
+}
+
+impl Ord for PokerHand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.kind.cmp(&other.kind)
+    }
+}
+
+impl PartialOrd for PokerHand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for PokerHand {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PokerHand {}
+
+/// The strongest five card hand that can be made from five to seven cards,
+/// as in Hold'em where two hole cards join five on the board.
+pub fn best_hand(cards: &[SuitedCard]) -> Result<PokerHand, PokerHandError> {
+    if !(5..=7).contains(&cards.len()) {
+        return Err(PokerHandError::WrongCardCount {
+            min: 5,
+            max: 7,
+            found: cards.len(),
+        });
+    }
+    let mut best: Option<PokerHand> = None;
+    // Each choice of five is the full set less up to two cards, where an
+    // index of `cards.len()` skips nothing.
+    for skip_a in 0..=cards.len() {
+        for skip_b in skip_a..=cards.len() {
+            let chosen: Vec<_> = cards
+                .iter()
+                .enumerate()
+                .filter(|(idx, _)| *idx != skip_a && *idx != skip_b)
+                .map(|(_, card)| card.to_owned())
+                .collect();
+            let Ok(chosen) = <[SuitedCard; 5]>::try_from(chosen) else {
+                continue;
+            };
+            let hand = PokerHand::try_from(&chosen)?;
+            if best.as_ref().is_none_or(|best| hand > *best) {
+                best = Some(hand);
+            }
+        }
+    }
+    Ok(best.unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hand(value: &str) -> PokerHand {
+        PokerHand::try_from(value).unwrap()
+    }
+
+    #[test]
+    fn test_kinds() {
+        assert!(matches!(
+            hand("As Ks Qs Js Ts").kind,
+            PokerHandKind::StraightFlush(_)
+        ));
+        assert!(matches!(
+            hand("9c 9d 9h 9s 2c").kind,
+            PokerHandKind::FourOfAKind(_)
+        ));
+        assert!(matches!(
+            hand("9c 9d 9h 2s 2c").kind,
+            PokerHandKind::FullHouse(_)
+        ));
+        assert!(matches!(
+            hand("2h 7h 9h Jh Kh").kind,
+            PokerHandKind::Flush(_)
+        ));
+        assert!(matches!(
+            hand("6c 7d 8h 9s Tc").kind,
+            PokerHandKind::Straight(_)
+        ));
+        assert!(matches!(
+            hand("9c 9d 9h 3s 2c").kind,
+            PokerHandKind::ThreeOfAKind(_)
+        ));
+        assert!(matches!(
+            hand("9c 9d 3h 3s 2c").kind,
+            PokerHandKind::TwoPair(_)
+        ));
+        assert!(matches!(
+            hand("9c 9d 4h 3s 2c").kind,
+            PokerHandKind::Pair(_)
+        ));
+        assert!(matches!(
+            hand("9c 7d 4h 3s 2c").kind,
+            PokerHandKind::HighCard(_)
+        ));
+    }
+
+    #[test]
+    fn test_wheel() {
+        let wheel = hand("Ac 2d 3h 4s 5c");
+        assert!(matches!(
+            wheel.kind,
+            PokerHandKind::Straight(Straight { high: Card::Five })
+        ));
+        assert!(wheel < hand("2c 3d 4h 5s 6c"));
+        assert!(wheel > hand("Ac Ad Ah 4s 5c"));
+        assert!(matches!(
+            hand("Ah 2h 3h 4h 5h").kind,
+            PokerHandKind::StraightFlush(_)
+        ));
+        assert!(matches!(
+            hand("Qc Kd Ah 2s 3c").kind,
+            PokerHandKind::HighCard(_)
+        ));
+    }
+
+    #[test]
+    fn test_kicker_ties() {
+        assert!(hand("Ac Ad Kh Qs 3c") > hand("Ah As Kc Qd 2c"));
+        assert!(hand("Ac Ad Kh Qs 3c") == hand("Ah As Kc Qd 3d"));
+        assert!(hand("2h 7h 9h Jh Kh") < hand("2c 7c 9c Qc Kc"));
+    }
+
+    #[test]
+    fn test_invalid_hands() {
+        let ace = SuitedCard::try_from("Ac").unwrap();
+        assert_eq!(
+            PokerHand::try_from("Ac Ac Kh Qs 3c").unwrap_err(),
+            PokerHandError::DuplicateCard(ace)
+        );
+        let err = PokerHand::try_from("Ac Kh Qs 3c").unwrap_err();
+        assert_eq!(err.to_string(), "expected 5 cards, found 4");
+        assert!(matches!(
+            PokerHand::try_from("Ac Kh Qs 3c 1d"),
+            Err(PokerHandError::Card(SuitedCardError::InvalidCard(_)))
+        ));
+        let joker = SuitedCard {
+            card: Card::Joker,
+            suit: ace.suit,
+        };
+        let cards = [joker, ace, ace, ace, ace];
+        assert_eq!(
+            PokerHand::try_from(&cards).unwrap_err(),
+            PokerHandError::SuitedJoker
+        );
+    }
+
+    #[test]
+    fn test_best_of_seven() {
+        let cards = parse_suited_cards("Ah Kh 2c Jh 9d Qh Th").unwrap();
+        let best = best_hand(&cards).unwrap();
+        assert!(matches!(best.kind, PokerHandKind::StraightFlush(_)));
+        assert!(!best.cards.contains(&cards[2]));
+        let cards = parse_suited_cards("2c 2d 5h 5s 5c 9d 9h").unwrap();
+        assert!(best_hand(&cards).unwrap() == hand("5h 5s 5c 9d 9h"));
+        let cards = parse_suited_cards("As 3d 4h 5c 2s").unwrap();
+        assert!(best_hand(&cards).unwrap() == hand("As 3d 4h 5c 2s"));
+        let err = best_hand(&cards[..4]).unwrap_err();
+        assert_eq!(err.to_string(), "expected 5 to 7 cards, found 4");
+    }
+}
@@ -0,0 +1,203 @@
+use crate::cards::{Card, CardRules};
+use crate::hands::{GenericHand, HandError, TieBreak};
+use std::cmp::{Ord, Ordering, PartialEq, PartialOrd};
+
+const NUMBER_WORDS: [&str; 8] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven",
+];
+
+fn number_word(n: usize) -> String {
+    NUMBER_WORDS
+        .get(n)
+        .map(|word| word.to_string())
+        .unwrap_or_else(|| n.to_string())
+}
+
+/// Names a count pattern, like "three pairs" or "four of a kind plus a
+/// pair". Zeros and single cards are ignored.
+pub fn pattern_name(pattern: &[usize]) -> String {
+    let mut groups: Vec<_> = pattern.iter().filter(|count| **count > 1).collect();
+    groups.sort_by(|a, b| b.cmp(a));
+    groups.dedup();
+    let phrases: Vec<_> = groups
+        .into_iter()
+        .map(|size| {
+            let times = pattern.iter().filter(|count| *count == size).count();
+            match (size, times) {
+                (2, 1) => "a pair".to_string(),
+                (2, _) => format!("{} pairs", number_word(times)),
+                (_, 1) => format!("{} of a kind", number_word(*size)),
+                (_, _) => format!("{} sets of {}", number_word(times), number_word(*size)),
+            }
+        })
+        .collect();
+    if phrases.is_empty() {
+        return "high card".to_string();
+    }
+    phrases.join(" plus ")
+}
+
+/// A hand of any size, ranked by how its cards group together: compare the
+/// largest groups first, then the next largest, and so on. For five cards
+/// this is the same order as `GameHand`, and so are ties, which go to the
+/// higher card in the order dealt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SizedHand<const N: usize> {
+    pattern: [usize; N],
+    cards: [Card; N],
+    defining: Vec<Card>,
+}
+
+pub type ThreeCardHand = SizedHand<3>;
+pub type SevenCardHand = SizedHand<7>;
+
+impl<const N: usize> SizedHand<N> {
+    pub fn cards(&self) -> &[Card; N] {
+        &self.cards
+    }
+
+    /// See `GenericHand::count_pattern`.
+    pub fn pattern(&self) -> &[usize; N] {
+        &self.pattern
+    }
+
+    pub fn name(&self) -> String {
+        pattern_name(&self.pattern)
+    }
+
+    /// Compares by count pattern, then settles ties using `tie_break`, where
+    /// kickers are the cards of each group, largest group first.
+    pub fn cmp_by(&self, other: &Self, tie_break: TieBreak) -> Ordering {
+        match tie_break {
+            TieBreak::Positional => self.cmp(other),
+            TieBreak::Kicker => match self.pattern.cmp(&other.pattern) {
+                Ordering::Equal => self.defining.cmp(&other.defining),
+                neq => neq,
+            },
+        }
+    }
+}
+
+impl<const N: usize> From<&GenericHand<N>> for SizedHand<N> {
+    fn from(hand: &GenericHand<N>) -> Self {
+        Self {
+            pattern: hand.count_pattern(),
+            cards: hand.cards,
+            defining: hand.defining_cards(),
+        }
+    }
+}
+
+impl<const N: usize> TryFrom<(&str, CardRules)> for SizedHand<N> {
+    type Error = HandError;
+
+    fn try_from(value: (&str, CardRules)) -> Result<Self, Self::Error> {
+        Ok(Self::from(&GenericHand::<N>::try_from(value)?))
+    }
+}
+
+impl<const N: usize> TryFrom<&str> for SizedHand<N> {
+    type Error = HandError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::try_from((value, CardRules::Standard))
+    }
+}
+
+impl<const N: usize> Ord for SizedHand<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.pattern.cmp(&other.pattern) {
+            Ordering::Equal => self.cards.cmp(&other.cards),
+            neq => neq,
+        }
+    }
+}
+
+impl<const N: usize> PartialOrd for SizedHand<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hands::GameHand;
+
+    #[test]
+    fn test_wrong_size_rejected() {
+        assert!(ThreeCardHand::try_from("KK7").is_ok());
+        assert!(ThreeCardHand::try_from("KK77").is_err());
+        assert!(SevenCardHand::try_from("KK77").is_err());
+        assert!(GenericHand::<3>::try_from("KK").is_err());
+    }
+
+    #[test]
+    fn test_three_card_order() {
+        let hands: Vec<ThreeCardHand> = ["AKQ", "22K", "KK2", "333", "J22"]
+            .iter()
+            .map(|h| ThreeCardHand::try_from(*h).unwrap())
+            .collect();
+        let mut sorted = hands.clone();
+        sorted.sort();
+        let names: Vec<_> = sorted.iter().map(|hand| hand.name()).collect();
+        assert_eq!(
+            names,
+            ["high card", "a pair", "a pair", "a pair", "three of a kind"]
+        );
+        assert_eq!(sorted[1], hands[1]);
+        assert_eq!(sorted[3], hands[2]);
+    }
+
+    #[test]
+    fn test_seven_card_patterns() {
+        let cases = [
+            ("2233445", "three pairs"),
+            ("KKKK332", "four of a kind plus a pair"),
+            ("KKK3332", "two sets of three"),
+            ("AKQJT98", "high card"),
+            ("7777777", "seven of a kind"),
+        ];
+        for (hand, name) in cases {
+            assert_eq!(SevenCardHand::try_from(hand).unwrap().name(), name);
+        }
+        let three_pairs = SevenCardHand::try_from("2233445").unwrap();
+        let full_house = SevenCardHand::try_from("2223345").unwrap();
+        assert!(three_pairs < full_house);
+    }
+
+    #[test]
+    fn test_jokers_join_best_group() {
+        let hand = SevenCardHand::try_from(("KKJ3345", CardRules::JokersWild)).unwrap();
+        assert_eq!(hand.name(), "three of a kind plus a pair");
+    }
+
+    #[test]
+    fn test_five_cards_match_game_hand() {
+        for rules in [CardRules::Standard, CardRules::JokersWild] {
+            let lines: Vec<_> = include_str!("../input_test.txt")
+                .lines()
+                .map(|line| &line[..5])
+                .collect();
+            for a in &lines {
+                for b in &lines {
+                    let game = GameHand::try_from((*a, rules))
+                        .unwrap()
+                        .cmp(&GameHand::try_from((*b, rules)).unwrap());
+                    let sized = SizedHand::<5>::try_from((*a, rules))
+                        .unwrap()
+                        .cmp(&SizedHand::<5>::try_from((*b, rules)).unwrap());
+                    assert_eq!(game, sized);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_kicker_tie_break() {
+        let a = SevenCardHand::try_from("A223344").unwrap();
+        let b = SevenCardHand::try_from("2334455").unwrap();
+        assert_eq!(a.cmp_by(&b, TieBreak::Positional), Ordering::Greater);
+        assert_eq!(a.cmp_by(&b, TieBreak::Kicker), Ordering::Less);
+    }
+}